@@ -0,0 +1,124 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::Mutex,
+};
+
+use solana_client::{client_error::Result as ClientResult, rpc_request::MAX_MULTIPLE_ACCOUNTS};
+use solana_program::{instruction::Instruction, pubkey::Pubkey};
+use solana_sdk::{account::Account, clock::Slot, signature::Signer};
+
+use crate::Miner;
+
+/// Accounts prefetched before sending a batch, keyed by address along with the slot they
+/// were read at. Addresses that do not exist on chain are cached as `None`.
+#[derive(Default)]
+pub struct AccountCache {
+    accounts: Mutex<HashMap<Pubkey, (Slot, Option<Account>)>>,
+}
+
+impl AccountCache {
+    pub fn get(&self, pubkey: &Pubkey) -> Option<Account> {
+        self.accounts
+            .lock()
+            .expect("failed to lock mutex")
+            .get(pubkey)
+            .and_then(|(_, account)| account.clone())
+    }
+
+    /// Adds accounts read at `slot`, keeping any entry already read at a later one, so
+    /// batches warming the cache concurrently do not drop or roll back each other's
+    /// accounts.
+    fn merge(&self, slot: Slot, fetched: impl IntoIterator<Item = (Pubkey, Option<Account>)>) {
+        let mut accounts = self.accounts.lock().expect("failed to lock mutex");
+        for (pubkey, account) in fetched {
+            match accounts.get(&pubkey) {
+                Some((cached_slot, _)) if *cached_slot > slot => {}
+                _ => {
+                    accounts.insert(pubkey, (slot, account));
+                }
+            }
+        }
+    }
+}
+
+impl Miner {
+    /// Fetches the fee payers and every account referenced by the batch using as few
+    /// `getMultipleAccounts` calls as possible.
+    pub async fn warm_account_cache(
+        &self,
+        payer: Pubkey,
+        txs_ixs: &[Vec<Instruction>],
+    ) -> ClientResult<()> {
+        let mut pubkeys = BTreeSet::from([payer]);
+        if let Some(multiplexer) = self.wallet_multiplexer.as_ref() {
+            pubkeys.extend(multiplexer.pubkeys());
+        }
+        if let Some(rotator) = self.key_rotator.as_ref() {
+            pubkeys.insert(rotator.current().pubkey());
+        }
+        for ix in txs_ixs.iter().flatten() {
+            pubkeys.extend(ix.accounts.iter().map(|meta| meta.pubkey));
+        }
+        let pubkeys: Vec<Pubkey> = pubkeys.into_iter().collect();
+
+        let client = self.rpc_client.clone();
+        for chunk in pubkeys.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            let res = self
+                .guarded_rpc(
+                    client.get_multiple_accounts_with_commitment(chunk, client.commitment()),
+                )
+                .await?;
+            for (pubkey, account) in chunk.iter().zip(res.value.iter()) {
+                let data_len = account.as_ref().map_or(0, |account| account.data.len());
+                self.cost_model.observe_account(*pubkey, data_len);
+            }
+            self.account_cache
+                .merge(res.context.slot, chunk.iter().copied().zip(res.value));
+        }
+        Ok(())
+    }
+
+    /// Re-fetches the accounts of one transaction just before it is built, so its balance
+    /// check, fee payer selection, and cost model do not reuse the batch's prefetch, when
+    /// `--simulate-disable-caching` is set.
+    pub async fn refresh_account_cache(
+        &self,
+        payer: Pubkey,
+        ixs: &[Instruction],
+    ) -> ClientResult<()> {
+        if !self.simulate_disable_caching {
+            return Ok(());
+        }
+        self.warm_account_cache(payer, &[ixs.to_vec()]).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_program::pubkey::Pubkey;
+    use solana_sdk::account::Account;
+
+    use super::AccountCache;
+
+    const PAYER: Pubkey = Pubkey::new_from_array([1; 32]);
+    const OTHER: Pubkey = Pubkey::new_from_array([2; 32]);
+
+    fn account(lamports: u64) -> Option<Account> {
+        Some(Account {
+            lamports,
+            ..Account::default()
+        })
+    }
+
+    #[test]
+    fn merges_without_dropping_or_rolling_back_entries() {
+        let cache = AccountCache::default();
+        cache.merge(20, [(PAYER, account(2)), (OTHER, account(5))]);
+        // A batch that fetched earlier, and only the payer, finishes last
+        cache.merge(10, [(PAYER, account(1))]);
+        assert_eq!(cache.get(&PAYER).map(|account| account.lamports), Some(2));
+        assert_eq!(cache.get(&OTHER).map(|account| account.lamports), Some(5));
+        cache.merge(30, [(PAYER, None)]);
+        assert_eq!(cache.get(&PAYER), None);
+    }
+}
@@ -0,0 +1,91 @@
+use solana_account_decoder::{UiAccount, UiAccountEncoding};
+use solana_client::{
+    rpc_config::RpcSimulateTransactionAccountsConfig, rpc_response::RpcSimulateTransactionResult,
+};
+use solana_sdk::account::Account;
+
+use crate::{lifecycle::batch_println, transaction::MinerTransaction, utils::to_hex, Miner};
+
+/// Asks the simulation to return the post-simulation state of every account in the
+/// message, in the same order.
+pub fn simulation_accounts_config(
+    tx: &impl MinerTransaction,
+) -> RpcSimulateTransactionAccountsConfig {
+    RpcSimulateTransactionAccountsConfig {
+        encoding: Some(UiAccountEncoding::Base64),
+        addresses: tx
+            .static_account_keys()
+            .iter()
+            .map(|pubkey| pubkey.to_string())
+            .collect(),
+    }
+}
+
+/// Ranges of byte offsets where `before` and `after` differ, including bytes past the
+/// end of the shorter one.
+fn changed_ranges(before: &[u8], after: &[u8]) -> Vec<(usize, usize)> {
+    let len = before.len().max(after.len());
+    let mut ranges: Vec<(usize, usize)> = vec![];
+    for i in (0..len).filter(|i| before.get(*i) != after.get(*i)) {
+        match ranges.last_mut() {
+            Some((_, end)) if *end == i => *end = i + 1,
+            _ => ranges.push((i, i + 1)),
+        }
+    }
+    ranges
+}
+
+fn slice(data: &[u8], start: usize, end: usize) -> &[u8] {
+    &data[start.min(data.len())..end.min(data.len())]
+}
+
+impl Miner {
+    /// Prints each account whose lamports or data the simulation changed, compared with
+    /// `fresh_accounts` if given, in message order, and otherwise the state prefetched
+    /// into the account cache.
+    pub fn print_accounts_diff(
+        &self,
+        tx: &impl MinerTransaction,
+        result: &RpcSimulateTransactionResult,
+        fresh_accounts: Option<&[Option<Account>]>,
+    ) {
+        let Some(accounts) = result.accounts.as_ref() else {
+            batch_println!("Simulation did not return account states");
+            return;
+        };
+        for (i, (pubkey, after)) in tx.static_account_keys().iter().zip(accounts).enumerate() {
+            let before = match fresh_accounts {
+                Some(fresh_accounts) => fresh_accounts.get(i).cloned().flatten(),
+                None => self.account_cache.get(pubkey),
+            };
+            let after: Option<Account> = after.as_ref().and_then(UiAccount::decode);
+            let (lamports_before, data_before) =
+                before.map_or((0, vec![]), |account| (account.lamports, account.data));
+            let (lamports_after, data_after) =
+                after.map_or((0, vec![]), |account| (account.lamports, account.data));
+            let ranges = changed_ranges(&data_before, &data_after);
+            if lamports_before == lamports_after && ranges.is_empty() {
+                continue;
+            }
+
+            batch_println!("Account {}", pubkey);
+            if lamports_before != lamports_after {
+                batch_println!(
+                    "  lamports: {} -> {} ({:+})",
+                    lamports_before,
+                    lamports_after,
+                    lamports_after as i128 - lamports_before as i128
+                );
+            }
+            for (start, end) in ranges {
+                batch_println!(
+                    "  data[{}..{}]: {} -> {}",
+                    start,
+                    end,
+                    to_hex(slice(&data_before, start, end)),
+                    to_hex(slice(&data_after, start, end))
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use std::fmt;
+
+use solana_client::{client_error::Result as ClientResult, rpc_config::RpcTransactionConfig};
+use solana_sdk::{clock::Slot, commitment_config::CommitmentConfig, signature::Signature};
+use solana_transaction_status::{
+    option_serializer::OptionSerializer, UiTransactionEncoding, UiTransactionStatusMeta,
+};
+
+use crate::{
+    compute_budget::{transaction_compute_unit_limit, transaction_compute_unit_price},
+    transaction::MinerTransaction,
+    Miner,
+};
+
+pub const LAMPORTS_PER_SIGNATURE: u64 = 5000;
+const MICROLAMPORTS_PER_LAMPORT: u64 = 1_000_000;
+
+/// Per-transaction details gathered while a transaction is built, sent, and confirmed.
+#[derive(Clone, Debug, Default)]
+pub struct TransactionAnnotation {
+    pub simulation_cus: Option<u64>,
+    pub actual_cus: Option<u64>,
+    pub priority_fee: u64,
+    pub fee_lamports: u64,
+    pub confirmation_slot: Option<Slot>,
+    pub confirmation_latency_ms: Option<u64>,
+}
+
+impl TransactionAnnotation {
+    /// Annotates a signed transaction with the fees it will pay at its compute unit limit.
+    pub fn new(tx: &impl MinerTransaction, simulation_cus: Option<u64>) -> Self {
+        let priority_fee = transaction_compute_unit_price(tx);
+        let cu_limit = transaction_compute_unit_limit(tx) as u64;
+        let prioritization_fee = (priority_fee as u128 * cu_limit as u128)
+            .div_ceil(MICROLAMPORTS_PER_LAMPORT as u128) as u64;
+        let signature_fee = LAMPORTS_PER_SIGNATURE * tx.num_required_signatures() as u64;
+        Self {
+            simulation_cus,
+            priority_fee,
+            fee_lamports: signature_fee.saturating_add(prioritization_fee),
+            ..Default::default()
+        }
+    }
+}
+
+fn fmt_option(value: Option<u64>) -> String {
+    value.map_or("-".into(), |value| value.to_string())
+}
+
+impl fmt::Display for TransactionAnnotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "simulated {} CUs, used {} CUs, priority fee {} microlamports, fee {} lamports, \
+             confirmed in slot {} after {} ms",
+            fmt_option(self.simulation_cus),
+            fmt_option(self.actual_cus),
+            self.priority_fee,
+            self.fee_lamports,
+            fmt_option(self.confirmation_slot),
+            fmt_option(self.confirmation_latency_ms),
+        )
+    }
+}
+
+impl Miner {
+    /// Looks up the status metadata of a confirmed transaction, including the compute units
+    /// it actually consumed and the fee it was charged.
+    pub async fn fetch_transaction_meta(
+        &self,
+        sig: &Signature,
+    ) -> ClientResult<Option<UiTransactionStatusMeta>> {
+        let config = RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Base64),
+            commitment: Some(CommitmentConfig::confirmed()),
+            max_supported_transaction_version: Some(0),
+        };
+        let tx = self
+            .guarded_rpc(self.rpc_client.get_transaction_with_config(sig, config))
+            .await?;
+        Ok(tx.transaction.meta)
+    }
+}
+
+pub fn consumed_cus(meta: &UiTransactionStatusMeta) -> Option<u64> {
+    match meta.compute_units_consumed {
+        OptionSerializer::Some(units) => Some(units),
+        _ => None,
+    }
+}
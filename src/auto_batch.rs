@@ -0,0 +1,262 @@
+use solana_program::{instruction::Instruction, pubkey::Pubkey};
+use solana_sdk::{
+    compute_budget::ComputeBudgetInstruction, packet::PACKET_DATA_SIZE, transaction::Transaction,
+};
+
+use crate::{
+    compute_budget::{parse_compute_budget_ix, MAX_COMPUTE_UNIT_LIMIT},
+    error::MinerError,
+};
+
+/// Returns the serialized size of a legacy transaction built from `ixs`, reserving room for
+/// the compute budget instructions added after simulation when `dynamic_cus` is set.
+pub fn transaction_size(ixs: &[Instruction], payer: &Pubkey, dynamic_cus: bool) -> usize {
+    let mut final_ixs = Vec::with_capacity(ixs.len() + 2);
+    if dynamic_cus {
+        final_ixs.push(ComputeBudgetInstruction::set_compute_unit_limit(0));
+        final_ixs.push(ComputeBudgetInstruction::set_compute_unit_price(0));
+    }
+    final_ixs.extend_from_slice(ixs);
+    let tx = Transaction::new_with_payer(&final_ixs, Some(payer));
+    bincode::serialized_size(&tx).map_or(usize::MAX, |size| size as usize)
+}
+
+/// Fails if `ixs` do not fit in a single transaction.
+pub fn check_fits(
+    ixs: &[Instruction],
+    payer: &Pubkey,
+    dynamic_cus: bool,
+) -> Result<(), MinerError> {
+    let size = transaction_size(ixs, payer, dynamic_cus);
+    if size > PACKET_DATA_SIZE {
+        return Err(MinerError::TransactionTooLarge {
+            size,
+            max: PACKET_DATA_SIZE,
+        });
+    }
+    Ok(())
+}
+
+/// How `auto_batch` divides instructions between transactions.
+#[derive(Clone, Copy, Debug, Default)]
+pub enum BatchSplitStrategy {
+    /// As few transactions as fit in a packet each
+    #[default]
+    GreedyFit,
+    /// Exactly N transactions of as equal a number of instructions as possible
+    EqualSplit(usize),
+    /// One transaction per instruction, to submit as many concurrently as possible
+    MaxParallelism,
+}
+
+/// Parses `greedy-fit`, `max-parallelism`, or `equal-split=<N>`.
+pub fn parse_batch_split_strategy(arg: &str) -> Result<BatchSplitStrategy, String> {
+    match arg.split_once('=') {
+        Some(("equal-split", count)) => match count.parse() {
+            Ok(0) => Err("equal-split needs at least one transaction".to_string()),
+            Ok(count) => Ok(BatchSplitStrategy::EqualSplit(count)),
+            Err(err) => Err(format!("invalid transaction count `{}`: {}", count, err)),
+        },
+        None if arg == "greedy-fit" => Ok(BatchSplitStrategy::GreedyFit),
+        None if arg == "max-parallelism" => Ok(BatchSplitStrategy::MaxParallelism),
+        _ => Err(format!(
+            "expected greedy-fit, max-parallelism, or equal-split=<N>, got `{}`",
+            arg
+        )),
+    }
+}
+
+/// Splits `ixs`, in order, into transactions that fit in a packet each, as `strategy`
+/// directs. Compute budget instructions are repeated in every transaction, so each
+/// keeps the budget and priority fee the whole set asked for. Room is left in every
+/// transaction for `reserved`, instructions added to each one after batching.
+pub fn auto_batch(
+    ixs: &[Instruction],
+    payer: &Pubkey,
+    dynamic_cus: bool,
+    strategy: BatchSplitStrategy,
+    reserved: &[Instruction],
+) -> Result<Vec<Vec<Instruction>>, MinerError> {
+    let (budget_ixs, other_ixs) = split_compute_budget(ixs);
+    let mut chunks: Vec<&[Instruction]> = match strategy {
+        BatchSplitStrategy::GreedyFit => {
+            let prefix = [reserved, budget_ixs.as_slice()].concat();
+            greedy_fit(&prefix, &other_ixs, payer, dynamic_cus)
+        }
+        BatchSplitStrategy::EqualSplit(count) => {
+            if count > other_ixs.len().max(1) {
+                return Err(MinerError::InvalidBatchSplit {
+                    transactions: count,
+                    instructions: other_ixs.len(),
+                });
+            }
+            // Earlier transactions take one extra instruction each until the rest divide evenly
+            let (base, extra) = (other_ixs.len() / count, other_ixs.len() % count);
+            let mut rest = other_ixs.as_slice();
+            (0..count)
+                .map(|i| {
+                    let (chunk, tail) = rest.split_at(base + usize::from(i < extra));
+                    rest = tail;
+                    chunk
+                })
+                .collect()
+        }
+        BatchSplitStrategy::MaxParallelism => other_ixs.chunks(1).collect(),
+    };
+    if chunks.is_empty() {
+        chunks.push(&[]);
+    }
+    let txs_ixs: Vec<Vec<Instruction>> = chunks
+        .into_iter()
+        .map(|chunk| [budget_ixs.as_slice(), chunk].concat())
+        .collect();
+    for ixs in txs_ixs.iter() {
+        check_fits(&[reserved, ixs].concat(), payer, dynamic_cus)?;
+    }
+    Ok(txs_ixs)
+}
+
+/// Separates compute budget instructions from the rest, keeping the order of each.
+fn split_compute_budget(ixs: &[Instruction]) -> (Vec<Instruction>, Vec<Instruction>) {
+    ixs.iter()
+        .cloned()
+        .partition(|ix| parse_compute_budget_ix(ix).is_some())
+}
+
+/// Packs `ixs`, in order, into as few chunks as fit in a packet each alongside
+/// `prefix`. A single instruction too large to fit still gets a chunk of its own.
+fn greedy_fit<'a>(
+    prefix: &[Instruction],
+    ixs: &'a [Instruction],
+    payer: &Pubkey,
+    dynamic_cus: bool,
+) -> Vec<&'a [Instruction]> {
+    let mut chunks = vec![];
+    let mut start = 0;
+    for end in 1..=ixs.len() {
+        let candidate = [prefix, &ixs[start..end]].concat();
+        if end - start > 1 && check_fits(&candidate, payer, dynamic_cus).is_err() {
+            chunks.push(&ixs[start..end - 1]);
+            start = end - 1;
+        }
+    }
+    if start < ixs.len() {
+        chunks.push(&ixs[start..]);
+    }
+    chunks
+}
+
+/// Rewrites batched transactions so they are valid and no larger than needed.
+pub struct BatchOptimizer;
+
+impl BatchOptimizer {
+    /// Leaves each transaction with at most one compute unit limit and one compute unit
+    /// price, placed first. Batched groups repeat the budget they share, so summing the
+    /// copies would inflate it; the largest limit is kept instead, capped at
+    /// `MAX_COMPUTE_UNIT_LIMIT`, along with the highest price any group asked for.
+    pub fn merge_compute_budget(txs: &mut [Vec<Instruction>]) {
+        for ixs in txs.iter_mut() {
+            let mut limits: Vec<u32> = vec![];
+            let mut prices: Vec<u64> = vec![];
+            for ix in ixs.iter() {
+                match parse_compute_budget_ix(ix) {
+                    Some(ComputeBudgetInstruction::SetComputeUnitLimit(units)) => {
+                        limits.push(units)
+                    }
+                    Some(ComputeBudgetInstruction::SetComputeUnitPrice(microlamports)) => {
+                        prices.push(microlamports)
+                    }
+                    _ => {}
+                }
+            }
+            if limits.len() + prices.len() <= 1 {
+                continue;
+            }
+
+            let limit = limits
+                .iter()
+                .max()
+                .map(|units| (*units).min(MAX_COMPUTE_UNIT_LIMIT));
+            let price = prices.iter().max().copied();
+            ixs.retain(|ix| {
+                !matches!(
+                    parse_compute_budget_ix(ix),
+                    Some(ComputeBudgetInstruction::SetComputeUnitLimit(_))
+                        | Some(ComputeBudgetInstruction::SetComputeUnitPrice(_))
+                )
+            });
+            let merged = limit
+                .map(ComputeBudgetInstruction::set_compute_unit_limit)
+                .into_iter()
+                .chain(price.map(ComputeBudgetInstruction::set_compute_unit_price));
+            ixs.splice(0..0, merged);
+        }
+    }
+
+    /// Removes instructions identical in program id, accounts, and data to an earlier one,
+    /// returning the removed duplicates.
+    pub fn dedup_instructions(ixs: &mut Vec<Instruction>) -> Vec<Instruction> {
+        let mut unique: Vec<Instruction> = Vec::with_capacity(ixs.len());
+        let mut removed = vec![];
+        for ix in ixs.drain(..) {
+            if unique.contains(&ix) {
+                removed.push(ix);
+            } else {
+                unique.push(ix);
+            }
+        }
+        *ixs = unique;
+        removed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_program::{instruction::Instruction, pubkey::Pubkey};
+    use solana_sdk::compute_budget::ComputeBudgetInstruction;
+
+    use super::BatchOptimizer;
+
+    const SIGNER: Pubkey = Pubkey::new_from_array([1; 32]);
+
+    #[test]
+    fn merges_shared_compute_budget_into_one_copy() {
+        let limit = ComputeBudgetInstruction::set_compute_unit_limit(200_000);
+        let price = ComputeBudgetInstruction::set_compute_unit_price(5_000);
+        let reset = ore::instruction::reset(SIGNER);
+        // Two items batched together, each carrying the budget they share
+        let mut txs: Vec<Vec<Instruction>> = vec![vec![
+            limit.clone(),
+            price.clone(),
+            reset.clone(),
+            limit.clone(),
+            price.clone(),
+            reset.clone(),
+        ]];
+        BatchOptimizer::merge_compute_budget(&mut txs);
+        assert_eq!(txs, vec![vec![limit, price, reset.clone(), reset]]);
+    }
+
+    #[test]
+    fn keeps_largest_limit_and_highest_price() {
+        let reset = ore::instruction::reset(SIGNER);
+        let mut txs: Vec<Vec<Instruction>> = vec![vec![
+            ComputeBudgetInstruction::set_compute_unit_limit(100_000),
+            ComputeBudgetInstruction::set_compute_unit_price(9_000),
+            reset.clone(),
+            ComputeBudgetInstruction::set_compute_unit_limit(300_000),
+            ComputeBudgetInstruction::set_compute_unit_price(1_000),
+            reset.clone(),
+        ]];
+        BatchOptimizer::merge_compute_budget(&mut txs);
+        assert_eq!(
+            txs,
+            vec![vec![
+                ComputeBudgetInstruction::set_compute_unit_limit(300_000),
+                ComputeBudgetInstruction::set_compute_unit_price(9_000),
+                reset.clone(),
+                reset,
+            ]]
+        );
+    }
+}
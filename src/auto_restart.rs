@@ -0,0 +1,73 @@
+use std::{
+    ffi::OsString,
+    io,
+    process::{Command, ExitStatus},
+    thread,
+    time::{Duration, Instant},
+};
+
+// Flags that make a process supervise, which are left off the child's arguments
+const SUPERVISOR_MODE_FLAG: &str = "--supervisor-mode";
+const AUTO_RESTART_FLAG: &str = "--auto-restart";
+
+// Exit code of a Rust process that panicked
+const PANIC_EXIT_CODE: i32 = 101;
+
+// Longest wait between restarts. A child that ran this long resets the backoff.
+const MAX_RESTART_DELAY: Duration = Duration::from_secs(300);
+
+/// Whether the child died to a panic or a signal, such as the OOM killer's SIGKILL, rather
+/// than exiting on its own.
+fn crashed(status: &ExitStatus) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if status.signal().is_some() {
+            return true;
+        }
+    }
+    status.code() == Some(PANIC_EXIT_CODE)
+}
+
+/// Keeps the miner alive by running it as a child process and respawning it with the same
+/// arguments whenever it panics or is killed.
+pub struct AutoRestarter {
+    delay: Duration,
+}
+
+impl AutoRestarter {
+    pub fn new(delay: Duration) -> Self {
+        Self { delay }
+    }
+
+    /// Runs the child until it exits other than by crashing, returning its final status.
+    /// The delay before each restart doubles while the child keeps crashing soon after
+    /// it starts.
+    pub fn run(&self) -> io::Result<ExitStatus> {
+        let exe = std::env::current_exe()?;
+        let args: Vec<OsString> = std::env::args_os()
+            .skip(1)
+            .filter(|arg| arg != AUTO_RESTART_FLAG && arg != SUPERVISOR_MODE_FLAG)
+            .collect();
+        let mut delay = self.delay;
+        loop {
+            let started_at = Instant::now();
+            let status = Command::new(&exe).args(&args).status()?;
+            if !crashed(&status) {
+                return Ok(status);
+            }
+            if started_at.elapsed() >= MAX_RESTART_DELAY {
+                delay = self.delay;
+            }
+            println!(
+                "Miner exited with {}, restarting in {}s",
+                status,
+                delay.as_secs()
+            );
+            thread::sleep(delay);
+            delay = delay
+                .saturating_mul(2)
+                .min(MAX_RESTART_DELAY.max(self.delay));
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use serde_json::json;
+use solana_program::pubkey::Pubkey;
+
+use crate::{lifecycle::batch_println, Miner};
+
+/// Posts to a webhook when the fee payer's balance falls below a threshold, repeating
+/// every `interval` until it recovers.
+pub struct BalanceAlert {
+    threshold: u64,
+    interval: Duration,
+    webhook_url: String,
+    client: reqwest::Client,
+    alerted: AtomicBool,
+    last_alert: Mutex<Option<Instant>>,
+}
+
+impl BalanceAlert {
+    pub fn new(threshold: u64, interval: Duration, webhook_url: String) -> Self {
+        Self {
+            threshold,
+            interval,
+            webhook_url,
+            client: reqwest::Client::new(),
+            alerted: AtomicBool::new(false),
+            last_alert: Mutex::new(None),
+        }
+    }
+
+    pub fn observe(&self, payer: &Pubkey, balance: u64) {
+        if balance >= self.threshold {
+            if self.alerted.swap(false, Ordering::Relaxed) {
+                batch_println!("Balance of {} recovered to {} lamports", payer, balance);
+            }
+            return;
+        }
+
+        let mut last_alert = self.last_alert.lock().expect("failed to lock mutex");
+        let first = !self.alerted.swap(true, Ordering::Relaxed);
+        if !first && last_alert.is_some_and(|at| at.elapsed() < self.interval) {
+            return;
+        }
+        *last_alert = Some(Instant::now());
+
+        let text = format!(
+            "Balance of {} is {} lamports, below the alert threshold of {}",
+            payer, balance, self.threshold
+        );
+        batch_println!("{}", text);
+        let body = json!({
+            "text": text,
+            "pubkey": payer.to_string(),
+            "balance_lamports": balance,
+            "threshold_lamports": self.threshold,
+        });
+        // Sending never waits on the webhook
+        let request = self.client.post(&self.webhook_url).json(&body);
+        tokio::spawn(async move {
+            if let Err(err) = request.send().await.and_then(|res| res.error_for_status()) {
+                println!("Failed to send balance alert: {}", err);
+            }
+        });
+    }
+}
+
+impl Miner {
+    pub fn observe_balance(&self, payer: &Pubkey, balance: u64) {
+        if let Some(alert) = self.balance_alert.as_ref() {
+            alert.observe(payer, balance);
+        }
+    }
+}
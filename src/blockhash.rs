@@ -0,0 +1,124 @@
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use serde_json::json;
+use solana_client::{
+    client_error::{ClientError, ClientErrorKind, Result as ClientResult},
+    rpc_request::RpcRequest,
+    rpc_response::{Response, RpcBlockhash},
+};
+use solana_sdk::{clock::Slot, commitment_config::CommitmentConfig, hash::Hash};
+
+use crate::{lifecycle::batch_println, Miner};
+
+const BLOCKHASH_LAG_RETRIES: usize = 10;
+const BLOCKHASH_LAG_DELAY: u64 = 400;
+
+// Blockhashes stay valid for about a minute, so one is safe to share for a few slots
+const BLOCKHASH_CACHE_TTL: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Copy)]
+pub struct LatestBlockhash {
+    pub hash: Hash,
+    pub last_valid_block_height: u64,
+    /// Slot of the bank the blockhash was read from
+    pub slot: Slot,
+    /// Whether the blockhash was reused from the `BlockhashCache`
+    pub cached: bool,
+}
+
+/// The last blockhash fetched, reused by transactions built shortly after it.
+#[derive(Default)]
+pub struct BlockhashCache {
+    latest: Mutex<Option<(Instant, LatestBlockhash)>>,
+}
+
+impl BlockhashCache {
+    fn get(&self) -> Option<LatestBlockhash> {
+        let latest = self.latest.lock().expect("failed to lock mutex");
+        let (fetched_at, blockhash) = latest.as_ref()?;
+        (fetched_at.elapsed() < BLOCKHASH_CACHE_TTL).then_some(LatestBlockhash {
+            cached: true,
+            ..*blockhash
+        })
+    }
+
+    fn insert(&self, blockhash: LatestBlockhash) {
+        *self.latest.lock().expect("failed to lock mutex") = Some((Instant::now(), blockhash));
+    }
+}
+
+impl Miner {
+    /// The latest blockhash, reused from the `BlockhashCache` if one was fetched within the
+    /// last second, unless `--simulate-disable-caching` is set.
+    pub async fn latest_blockhash(&self) -> ClientResult<LatestBlockhash> {
+        if !self.simulate_disable_caching {
+            if let Some(blockhash) = self.blockhash_cache.get() {
+                return Ok(blockhash);
+            }
+        }
+        let blockhash = self.fetch_lagged_blockhash().await?;
+        self.blockhash_cache.insert(blockhash);
+        Ok(blockhash)
+    }
+
+    /// Fetches the latest blockhash, retrying until it is at least `--blockhash-min-slot-lag`
+    /// slots behind the live slot. Falls back to the last one fetched if it never is.
+    async fn fetch_lagged_blockhash(&self) -> ClientResult<LatestBlockhash> {
+        let mut attempts = 0;
+        loop {
+            let blockhash = self.fetch_latest_blockhash().await?;
+            if self.blockhash_min_slot_lag == 0 {
+                return Ok(blockhash);
+            }
+            let live_slot = self
+                .guarded_rpc(
+                    self.read_client()
+                        .get_slot_with_commitment(CommitmentConfig::processed()),
+                )
+                .await?;
+            attempts += 1;
+            if live_slot.saturating_sub(blockhash.slot) >= self.blockhash_min_slot_lag
+                || attempts >= BLOCKHASH_LAG_RETRIES
+            {
+                return Ok(blockhash);
+            }
+            tokio::time::sleep(Duration::from_millis(BLOCKHASH_LAG_DELAY)).await;
+        }
+    }
+
+    async fn fetch_latest_blockhash(&self) -> ClientResult<LatestBlockhash> {
+        // Request by hand to keep the context slot the typed client discards
+        let client = self.read_client();
+        let res: Response<RpcBlockhash> = self
+            .guarded_rpc(client.send(RpcRequest::GetLatestBlockhash, json!([client.commitment()])))
+            .await?;
+        let hash = res.value.blockhash.parse().map_err(|_| ClientError {
+            request: None,
+            kind: ClientErrorKind::Custom(format!("Invalid blockhash: {}", res.value.blockhash)),
+        })?;
+        Ok(LatestBlockhash {
+            hash,
+            last_valid_block_height: res.value.last_valid_block_height,
+            slot: res.context.slot,
+            cached: false,
+        })
+    }
+
+    pub fn log_blockhash(&self, blockhash: &LatestBlockhash) {
+        if self.print_blockhash {
+            batch_println!(
+                "Signing with blockhash {} from slot {} ({})",
+                blockhash.hash,
+                blockhash.slot,
+                if blockhash.cached {
+                    "reused from cache"
+                } else {
+                    "freshly fetched"
+                }
+            );
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use solana_program::hash::Hash;
+use solana_sdk::clock::Slot;
+
+use crate::{error::MinerError, Miner};
+
+const BUNDLE_POLL_DELAY: u64 = 2000;
+// Polls made once the nonce changes, in case the bundle itself advanced it
+const NONCE_CHANGE_POLL_ATTEMPTS: usize = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BundleStatus {
+    Landed { slot: Slot },
+    Failed,
+    Invalid,
+}
+
+/// Polls the Jito block engine until a submitted bundle lands or is rejected.
+pub struct BundleStatusPoller {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl BundleStatusPoller {
+    pub fn new(block_engine_url: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: format!("{}/api/v1/bundles", block_engine_url.trim_end_matches('/')),
+        }
+    }
+
+    pub async fn poll_until_landed(
+        &self,
+        bundle_id: &str,
+        max_attempts: usize,
+    ) -> Result<BundleStatus, MinerError> {
+        for attempt in 0..max_attempts {
+            if attempt > 0 {
+                tokio::time::sleep(Duration::from_millis(BUNDLE_POLL_DELAY)).await;
+            }
+
+            // Bundles that made it on chain are reported by getBundleStatuses
+            let status = self.request("getBundleStatuses", bundle_id).await?;
+            if let Some(slot) = status.get("slot").and_then(Value::as_u64) {
+                let succeeded = status.get("err").is_some_and(|err| err.get("Ok").is_some());
+                return Ok(if succeeded {
+                    BundleStatus::Landed { slot }
+                } else {
+                    BundleStatus::Failed
+                });
+            }
+
+            // Everything else is only tracked by getInflightBundleStatuses
+            let status = self.request("getInflightBundleStatuses", bundle_id).await?;
+            match status.get("status").and_then(Value::as_str) {
+                Some("Landed") => {
+                    if let Some(slot) = status.get("landed_slot").and_then(Value::as_u64) {
+                        return Ok(BundleStatus::Landed { slot });
+                    }
+                }
+                Some("Failed") => return Ok(BundleStatus::Failed),
+                Some("Invalid") => return Ok(BundleStatus::Invalid),
+                _ => {}
+            }
+        }
+        Err(MinerError::BundleStatusUnavailable(format!(
+            "Bundle {} did not land after {} attempts",
+            bundle_id, max_attempts
+        )))
+    }
+
+    /// Returns the status entry for `bundle_id`, which is null while the engine has none.
+    async fn request(&self, method: &str, bundle_id: &str) -> Result<Value, MinerError> {
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": [[bundle_id]],
+        });
+        let mut response: Value = self
+            .client
+            .post(&self.url)
+            .json(&body)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|err| MinerError::BundleStatusUnavailable(err.to_string()))?
+            .json()
+            .await
+            .map_err(|err| MinerError::BundleStatusUnavailable(err.to_string()))?;
+        if let Some(err) = response.get("error") {
+            return Err(MinerError::BundleStatusUnavailable(err.to_string()));
+        }
+        Ok(response["result"]["value"][0].take())
+    }
+}
+
+impl Miner {
+    pub async fn bundle_status(
+        &self,
+        block_engine_url: &str,
+        bundle_id: &str,
+        max_attempts: usize,
+        tip_account_index: Option<usize>,
+        expected_nonce: Option<Hash>,
+    ) {
+        let poller = BundleStatusPoller::new(block_engine_url);
+        let tip_account = tip_account_index.and_then(|index| self.tip_account(index));
+        let monitor = match tip_account.zip(expected_nonce) {
+            Some((account, expected)) => match self.watch_nonce_account(account, expected).await {
+                Ok(monitor) => Some(monitor),
+                Err(err) => {
+                    println!("Failed to watch tip account {}: {}", account, err);
+                    None
+                }
+            },
+            None => None,
+        };
+        let status = match monitor {
+            // Landing advances the nonce too, so only a bundle that did not land has had
+            // its nonce consumed elsewhere
+            Some(mut monitor) => tokio::select! {
+                status = poller.poll_until_landed(bundle_id, max_attempts) => status,
+                err = monitor.consumed() => poller
+                    .poll_until_landed(bundle_id, NONCE_CHANGE_POLL_ATTEMPTS)
+                    .await
+                    .or(Err(err)),
+            },
+            None => poller.poll_until_landed(bundle_id, max_attempts).await,
+        };
+        match status {
+            Ok(BundleStatus::Landed { slot }) => {
+                println!("Bundle landed in slot {}", slot);
+                if let Some(account) = tip_account {
+                    self.advance_tip_account(account).await;
+                }
+            }
+            Ok(BundleStatus::Failed) => println!("Bundle failed"),
+            Ok(BundleStatus::Invalid) => println!("Bundle is invalid"),
+            Err(err) => println!("Error: {}", err),
+        }
+    }
+}
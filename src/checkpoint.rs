@@ -0,0 +1,96 @@
+use std::{fs, io, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    difficulty::DifficultyPredictor, fee_history::FeeSample, metrics::MetricsSnapshot,
+    mine::NonceRange, Miner,
+};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Checkpoint {
+    pub metrics: MetricsSnapshot,
+    #[serde(default)]
+    pub fee_history: Vec<FeeSample>,
+    #[serde(default)]
+    pub difficulty: Option<DifficultyPredictor>,
+    #[serde(default)]
+    pub nonce_range: Option<NonceRange>,
+}
+
+pub struct Checkpointer {
+    pub interval: u64,
+    pub path: PathBuf,
+}
+
+impl Checkpointer {
+    pub fn load(&self) -> Option<Checkpoint> {
+        let data = fs::read(&self.path).ok()?;
+        match serde_json::from_slice(&data) {
+            Ok(checkpoint) => Some(checkpoint),
+            Err(err) => {
+                println!("Ignoring unreadable checkpoint {:?}: {}", self.path, err);
+                None
+            }
+        }
+    }
+
+    pub fn save(&self, checkpoint: &Checkpoint) -> io::Result<()> {
+        // Write to a sibling file first so a crash mid-write never corrupts the checkpoint
+        let tmp_path = self.path.with_extension("tmp");
+        fs::write(&tmp_path, serde_json::to_vec_pretty(checkpoint)?)?;
+        fs::rename(tmp_path, &self.path)
+    }
+}
+
+impl Miner {
+    pub fn restore_checkpoint(&self) {
+        if let Some(checkpointer) = self.checkpointer.as_ref() {
+            if let Some(checkpoint) = checkpointer.load() {
+                println!("Restored checkpoint from {:?}", checkpointer.path);
+                self.metrics.restore(&checkpoint.metrics);
+                self.fee_history.restore(&checkpoint.fee_history);
+                if let Some(difficulty) = checkpoint.difficulty {
+                    *self
+                        .difficulty_predictor
+                        .lock()
+                        .expect("failed to lock mutex") = difficulty;
+                }
+                *self.nonce_range.lock().expect("failed to lock mutex") = checkpoint.nonce_range;
+            }
+        }
+    }
+
+    /// Saves a checkpoint after every `interval` confirmed transactions.
+    pub fn checkpoint_if_due(&self, confirmed: u64) {
+        if let Some(checkpointer) = self.checkpointer.as_ref() {
+            if confirmed % checkpointer.interval.max(1) == 0 {
+                self.save_checkpoint();
+            }
+        }
+    }
+
+    /// Saves a checkpoint now, when `--checkpoint-interval` is set.
+    pub fn save_checkpoint(&self) {
+        if let Some(checkpointer) = self.checkpointer.as_ref() {
+            let checkpoint = Checkpoint {
+                metrics: self.metrics.snapshot(),
+                fee_history: self.fee_history.samples(),
+                difficulty: Some(
+                    *self
+                        .difficulty_predictor
+                        .lock()
+                        .expect("failed to lock mutex"),
+                ),
+                nonce_range: self
+                    .nonce_range
+                    .lock()
+                    .expect("failed to lock mutex")
+                    .clone(),
+            };
+            if let Err(err) = checkpointer.save(&checkpoint) {
+                println!("Failed to save checkpoint: {:?}", err);
+            }
+        }
+    }
+}
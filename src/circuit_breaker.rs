@@ -0,0 +1,144 @@
+use std::{
+    future::Future,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use solana_client::client_error::Result as ClientResult;
+
+use crate::{error::MinerError, lifecycle::batch_println, Miner};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CircuitState {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+struct BreakerState {
+    state: CircuitState,
+    consecutive_failures: usize,
+    opened_at: Instant,
+    probe_in_flight: bool,
+}
+
+/// Stops issuing RPC calls after `threshold` consecutive failures, then lets a single
+/// probe request through once `cooldown` has elapsed.
+pub struct RpcCircuitBreaker {
+    threshold: usize,
+    cooldown: Duration,
+    inner: Mutex<BreakerState>,
+}
+
+impl RpcCircuitBreaker {
+    pub fn new(threshold: usize, cooldown: Duration) -> Self {
+        Self {
+            threshold: threshold.max(1),
+            cooldown,
+            inner: Mutex::new(BreakerState {
+                state: CircuitState::Closed,
+                consecutive_failures: 0,
+                opened_at: Instant::now(),
+                probe_in_flight: false,
+            }),
+        }
+    }
+
+    pub fn state(&self) -> CircuitState {
+        self.inner.lock().expect("failed to lock mutex").state
+    }
+
+    /// Returns an error if the circuit is open and no probe request may be sent.
+    pub fn allow(&self) -> Result<CallPermit<'_>, MinerError> {
+        let mut inner = self.inner.lock().expect("failed to lock mutex");
+        let probe = match inner.state {
+            CircuitState::Closed => false,
+            CircuitState::Open => {
+                if inner.opened_at.elapsed() < self.cooldown {
+                    return Err(MinerError::CircuitBreakerOpen);
+                }
+                inner.state = CircuitState::HalfOpen;
+                true
+            }
+            CircuitState::HalfOpen => {
+                if inner.probe_in_flight {
+                    return Err(MinerError::CircuitBreakerOpen);
+                }
+                true
+            }
+        };
+        inner.probe_in_flight |= probe;
+        Ok(CallPermit {
+            breaker: self,
+            probe,
+        })
+    }
+
+    fn record(&self, succeeded: bool, probe: bool) {
+        let mut inner = self.inner.lock().expect("failed to lock mutex");
+        if probe {
+            inner.probe_in_flight = false;
+        }
+        if succeeded {
+            inner.state = CircuitState::Closed;
+            inner.consecutive_failures = 0;
+            return;
+        }
+        inner.consecutive_failures += 1;
+        if inner.state == CircuitState::HalfOpen || inner.consecutive_failures >= self.threshold {
+            if inner.state != CircuitState::Open {
+                batch_println!(
+                    "RPC circuit breaker opened after {} consecutive failures",
+                    inner.consecutive_failures
+                );
+            }
+            inner.state = CircuitState::Open;
+            inner.opened_at = Instant::now();
+        }
+    }
+}
+
+/// Permission to make one RPC call. A permit dropped before `record`, as when a timeout
+/// or `select!` cancels the call, counts as a failure if it was the half-open probe, so
+/// the breaker never waits on a probe that can no longer report back.
+pub struct CallPermit<'a> {
+    breaker: &'a RpcCircuitBreaker,
+    probe: bool,
+}
+
+impl CallPermit<'_> {
+    pub fn record<T>(mut self, res: &ClientResult<T>) {
+        self.breaker.record(res.is_ok(), self.probe);
+        self.probe = false;
+    }
+}
+
+impl Drop for CallPermit<'_> {
+    fn drop(&mut self) {
+        if self.probe {
+            self.breaker.record(false, true);
+        }
+    }
+}
+
+impl Miner {
+    pub fn circuit_open(&self) -> bool {
+        self.circuit_breaker
+            .as_ref()
+            .is_some_and(|breaker| breaker.state() == CircuitState::Open)
+    }
+
+    /// Runs an RPC call through the circuit breaker, if one is configured.
+    pub async fn guarded_rpc<T>(
+        &self,
+        call: impl Future<Output = ClientResult<T>>,
+    ) -> ClientResult<T> {
+        let Some(breaker) = self.circuit_breaker.as_ref() else {
+            return call.await;
+        };
+        let permit = breaker.allow()?;
+        let res = call.await;
+        permit.record(&res);
+        res
+    }
+}
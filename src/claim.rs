@@ -1,11 +1,8 @@
 use std::str::FromStr;
 
 use ore::{self, state::Proof, utils::AccountDeserialize};
-use solana_program::pubkey::Pubkey;
-use solana_sdk::{
-    compute_budget::ComputeBudgetInstruction,
-    signature::Signer,
-};
+use solana_program::{instruction::Instruction, pubkey::Pubkey};
+use solana_sdk::{compute_budget::ComputeBudgetInstruction, signature::Signer};
 
 use crate::{cu_limits::CU_LIMIT_CLAIM, utils::proof_pubkey, Miner};
 
@@ -35,17 +32,12 @@ impl Miner {
             }
         };
         let amountf = (amount as f64) / (10f64.powf(ore::TOKEN_DECIMALS as f64));
-        let cu_limit_ix = ComputeBudgetInstruction::set_compute_unit_limit(CU_LIMIT_CLAIM);
-        let cu_price_ix = ComputeBudgetInstruction::set_compute_unit_price(self.priority_fee);
-        let ix = ore::instruction::claim(pubkey, beneficiary, amount);
+        let ixs = self.claim_ixs(pubkey, beneficiary, amount);
         println!("Submitting claim transaction...");
-        match self
-            .send_and_confirm(&[cu_limit_ix, cu_price_ix, ix], false, false)
-            .await
-        {
+        match self.send_and_confirm(&ixs, false, false).await {
             Ok(sig) => {
                 println!("Claimed {:} ORE to account {:}", amountf, beneficiary);
-                println!("{:?}", sig);
+                println!("{}", self.signature_display.format(&sig));
             }
             Err(err) => {
                 println!("Error: {:?}", err);
@@ -53,7 +45,15 @@ impl Miner {
         }
     }
 
-    async fn initialize_ata(&self) -> Pubkey {
+    pub fn claim_ixs(&self, pubkey: Pubkey, beneficiary: Pubkey, amount: u64) -> Vec<Instruction> {
+        let cu_limit_ix = ComputeBudgetInstruction::set_compute_unit_limit(CU_LIMIT_CLAIM);
+        let cu_price_ix =
+            ComputeBudgetInstruction::set_compute_unit_price(self.scheduled_priority_fee());
+        let ix = ore::instruction::claim(pubkey, beneficiary, amount);
+        vec![cu_limit_ix, cu_price_ix, ix]
+    }
+
+    pub async fn initialize_ata(&self) -> Pubkey {
         // Initialize client.
         let signer = self.signer();
         let client = self.rpc_client.clone();
@@ -0,0 +1,143 @@
+use std::fmt;
+
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use solana_program::{borsh1::try_from_slice_unchecked, instruction::Instruction};
+use solana_sdk::compute_budget::{self, ComputeBudgetInstruction};
+
+use crate::transaction::MinerTransaction;
+
+pub fn parse_compute_budget_ix(ix: &Instruction) -> Option<ComputeBudgetInstruction> {
+    if ix.program_id != compute_budget::id() {
+        return None;
+    }
+    try_from_slice_unchecked(&ix.data).ok()
+}
+
+/// Returns the compute unit price set by a signed transaction, in microlamports.
+pub fn transaction_compute_unit_price(tx: &impl MinerTransaction) -> u64 {
+    let keys = tx.static_account_keys();
+    tx.instructions()
+        .iter()
+        .filter(|ix| keys.get(ix.program_id_index as usize) == Some(&compute_budget::id()))
+        .find_map(|ix| match try_from_slice_unchecked(&ix.data) {
+            Ok(ComputeBudgetInstruction::SetComputeUnitPrice(price)) => Some(price),
+            _ => None,
+        })
+        .unwrap_or(0)
+}
+
+// Runtime defaults applied when a transaction does not request a compute unit limit
+const DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT: u32 = 200_000;
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+/// Returns the compute unit limit the runtime applies to `ixs` without simulating them.
+pub fn static_compute_unit_limit(ixs: &[Instruction]) -> u32 {
+    let (budget_ixs, other_ixs): (Vec<_>, Vec<_>) = ixs
+        .iter()
+        .partition(|ix| ix.program_id == compute_budget::id());
+    budget_ixs
+        .iter()
+        .find_map(|ix| match parse_compute_budget_ix(ix) {
+            Some(ComputeBudgetInstruction::SetComputeUnitLimit(limit)) => Some(limit),
+            _ => None,
+        })
+        .unwrap_or(DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT.saturating_mul(other_ixs.len() as u32))
+        .min(MAX_COMPUTE_UNIT_LIMIT)
+}
+
+/// Returns the compute unit limit a signed transaction will be charged against.
+pub fn transaction_compute_unit_limit(tx: &impl MinerTransaction) -> u32 {
+    let keys = tx.static_account_keys();
+    let (budget_ixs, other_ixs): (Vec<_>, Vec<_>) = tx
+        .instructions()
+        .iter()
+        .partition(|ix| keys.get(ix.program_id_index as usize) == Some(&compute_budget::id()));
+    budget_ixs
+        .iter()
+        .find_map(|ix| match try_from_slice_unchecked(&ix.data) {
+            Ok(ComputeBudgetInstruction::SetComputeUnitLimit(limit)) => Some(limit),
+            _ => None,
+        })
+        .unwrap_or(DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT.saturating_mul(other_ixs.len() as u32))
+        .min(MAX_COMPUTE_UNIT_LIMIT)
+}
+
+#[derive(Debug)]
+pub enum ValidationError {
+    ZeroComputeUnitLimit,
+    ComputeUnitLimitTooHigh { limit: u32, max: u32 },
+    DuplicateComputeUnitLimit,
+    DuplicateComputeUnitPrice,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::ZeroComputeUnitLimit => {
+                write!(
+                    f,
+                    "Compute unit limit is 0, so every instruction would fail"
+                )
+            }
+            ValidationError::ComputeUnitLimitTooHigh { limit, max } => write!(
+                f,
+                "Compute unit limit of {} is more than the maximum of {}",
+                limit, max
+            ),
+            ValidationError::DuplicateComputeUnitLimit => {
+                write!(f, "Transaction sets the compute unit limit more than once")
+            }
+            ValidationError::DuplicateComputeUnitPrice => {
+                write!(f, "Transaction sets the compute unit price more than once")
+            }
+        }
+    }
+}
+
+impl From<ValidationError> for ClientError {
+    fn from(err: ValidationError) -> Self {
+        ClientError {
+            request: None,
+            kind: ClientErrorKind::Custom(err.to_string()),
+        }
+    }
+}
+
+/// Catches compute budget instructions the runtime would reject before a transaction is sent.
+pub struct InstructionBudgetValidator;
+
+impl InstructionBudgetValidator {
+    /// Checks the limit is within (0, 1.4M] and that the limit and price are each set at
+    /// most once. Prices are unsigned, so any price is valid.
+    pub fn validate(ixs: &[Instruction]) -> Result<(), ValidationError> {
+        let mut has_limit = false;
+        let mut has_price = false;
+        for ix in ixs {
+            match parse_compute_budget_ix(ix) {
+                Some(ComputeBudgetInstruction::SetComputeUnitLimit(limit)) => {
+                    if has_limit {
+                        return Err(ValidationError::DuplicateComputeUnitLimit);
+                    }
+                    if limit == 0 {
+                        return Err(ValidationError::ZeroComputeUnitLimit);
+                    }
+                    if limit > MAX_COMPUTE_UNIT_LIMIT {
+                        return Err(ValidationError::ComputeUnitLimitTooHigh {
+                            limit,
+                            max: MAX_COMPUTE_UNIT_LIMIT,
+                        });
+                    }
+                    has_limit = true;
+                }
+                Some(ComputeBudgetInstruction::SetComputeUnitPrice(_)) => {
+                    if has_price {
+                        return Err(ValidationError::DuplicateComputeUnitPrice);
+                    }
+                    has_price = true;
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,204 @@
+use std::{
+    future::Future,
+    time::{Duration, Instant},
+};
+
+use clap::ValueEnum;
+use futures::{stream, StreamExt};
+use solana_client::{
+    nonblocking::pubsub_client::PubsubClient, rpc_config::RpcSignatureSubscribeConfig,
+    rpc_response::RpcSignatureResult,
+};
+use solana_sdk::{commitment_config::CommitmentConfig, signature::Signature};
+
+use crate::{lifecycle::batch_println, send_and_confirm::Confirmation, Miner};
+
+// Matches the time the polling path spends before giving up
+const SUBSCRIBE_TIMEOUT: Duration = Duration::from_millis(20_000);
+
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+pub enum ConfirmationStrategy {
+    /// Poll signature statuses over RPC
+    #[default]
+    Polling,
+    /// Wait for a `signatureSubscribe` notification
+    #[value(name = "websocket")]
+    WebSocket,
+    /// Run both and take whichever confirms first
+    Hybrid,
+}
+
+/// Runs both confirmation paths and returns the first confirmation either reports.
+/// Returning drops the other future, which cancels it. A path that gives up empty-handed
+/// leaves the other running until it finishes too.
+async fn race_confirmations(
+    polling: impl Future<Output = Option<Confirmation>>,
+    subscription: impl Future<Output = Option<Confirmation>>,
+) -> Option<Confirmation> {
+    tokio::pin!(polling, subscription);
+    let (mut polling_done, mut subscription_done) = (false, false);
+    loop {
+        tokio::select! {
+            confirmation = &mut polling, if !polling_done => match confirmation {
+                Some(confirmation) => return Some(confirmation),
+                None => polling_done = true,
+            },
+            confirmation = &mut subscription, if !subscription_done => match confirmation {
+                Some(confirmation) => return Some(confirmation),
+                None => subscription_done = true,
+            },
+            else => return None,
+        }
+    }
+}
+
+impl Miner {
+    /// Confirms the given signatures using the configured `ConfirmationStrategy`.
+    pub async fn await_confirmation(
+        &self,
+        sigs: &[Signature],
+        sent_at: Instant,
+    ) -> Option<Confirmation> {
+        match self.confirmation_strategy {
+            ConfirmationStrategy::Polling => self.poll_confirmations(sigs, sent_at).await,
+            ConfirmationStrategy::WebSocket => self.subscribe_confirmations(sigs, sent_at).await,
+            ConfirmationStrategy::Hybrid => {
+                race_confirmations(
+                    self.poll_confirmations(sigs, sent_at),
+                    self.subscribe_confirmations(sigs, sent_at),
+                )
+                .await
+            }
+        }
+    }
+
+    /// Waits for the first of the given signatures to be reported confirmed over websocket.
+    pub async fn subscribe_confirmations(
+        &self,
+        sigs: &[Signature],
+        sent_at: Instant,
+    ) -> Option<Confirmation> {
+        let client = match PubsubClient::new(&self.rpc_ws_url).await {
+            Ok(client) => client,
+            Err(err) => {
+                batch_println!("Error subscribing to signatures: {:?}", err);
+                return None;
+            }
+        };
+        let config = RpcSignatureSubscribeConfig {
+            commitment: Some(CommitmentConfig::confirmed()),
+            enable_received_notification: Some(false),
+        };
+
+        let mut subscriptions = Vec::new();
+        let mut unsubscribes = Vec::new();
+        for sig in sigs.iter().copied() {
+            match client.signature_subscribe(&sig, Some(config.clone())).await {
+                Ok((notifications, unsubscribe)) => {
+                    subscriptions.push(notifications.map(move |response| (sig, response)));
+                    unsubscribes.push(unsubscribe);
+                }
+                Err(err) => batch_println!("Error subscribing to signature: {:?}", err),
+            }
+        }
+        let mut notifications = stream::select_all(subscriptions);
+        let confirmed = tokio::time::timeout(SUBSCRIBE_TIMEOUT, async {
+            while let Some((sig, response)) = notifications.next().await {
+                if let RpcSignatureResult::ProcessedSignature(result) = response.value {
+                    return Some((sig, response.context.slot, result.err));
+                }
+            }
+            None
+        })
+        .await
+        .ok()
+        .flatten();
+        drop(notifications);
+        for unsubscribe in unsubscribes {
+            unsubscribe().await;
+        }
+
+        let (signature, slot, err) = confirmed?;
+        batch_println!("Transaction landed!");
+        let confirmed_at = Instant::now();
+        self.record_confirmation(slot, sent_at, confirmed_at, err.as_ref());
+        Some(Confirmation {
+            signature,
+            slot,
+            latency: confirmed_at.duration_since(sent_at),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use solana_sdk::signature::Signature;
+    use tokio::time::{sleep, Instant};
+
+    use super::race_confirmations;
+    use crate::send_and_confirm::Confirmation;
+
+    async fn confirm_after(delay: Duration, slot: u64) -> Option<Confirmation> {
+        sleep(delay).await;
+        Some(Confirmation {
+            signature: Signature::default(),
+            slot,
+            latency: delay,
+        })
+    }
+
+    async fn give_up_after(delay: Duration) -> Option<Confirmation> {
+        sleep(delay).await;
+        None
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn websocket_wins() {
+        let started = Instant::now();
+        let confirmation = race_confirmations(
+            confirm_after(Duration::from_secs(5), 1),
+            confirm_after(Duration::from_secs(1), 2),
+        )
+        .await;
+        assert_eq!(confirmation.map(|confirmation| confirmation.slot), Some(2));
+        assert_eq!(started.elapsed(), Duration::from_secs(1));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn polling_wins() {
+        let started = Instant::now();
+        let confirmation = race_confirmations(
+            confirm_after(Duration::from_secs(5), 1),
+            confirm_after(Duration::from_secs(20), 2),
+        )
+        .await;
+        assert_eq!(confirmation.map(|confirmation| confirmation.slot), Some(1));
+        assert_eq!(started.elapsed(), Duration::from_secs(5));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn keeps_waiting_after_one_path_gives_up() {
+        let started = Instant::now();
+        let confirmation = race_confirmations(
+            give_up_after(Duration::from_secs(1)),
+            confirm_after(Duration::from_secs(8), 2),
+        )
+        .await;
+        assert_eq!(confirmation.map(|confirmation| confirmation.slot), Some(2));
+        assert_eq!(started.elapsed(), Duration::from_secs(8));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn times_out_when_both_give_up() {
+        let started = Instant::now();
+        let confirmation = race_confirmations(
+            give_up_after(Duration::from_secs(20)),
+            give_up_after(Duration::from_secs(20)),
+        )
+        .await;
+        assert!(confirmation.is_none());
+        assert_eq!(started.elapsed(), Duration::from_secs(20));
+    }
+}
@@ -0,0 +1,90 @@
+use std::{
+    fmt::Write as _,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use solana_sdk::clock::Slot;
+
+use crate::{lifecycle::batch_println, slot_window::SlotWindow};
+
+const LATENCY_SAMPLES: usize = 1000;
+const REPORT_INTERVAL: Duration = Duration::from_secs(60);
+
+// Quantiles exported as `miner_confirm_latency_ms`
+const QUANTILES: [f64; 2] = [0.5, 0.99];
+
+/// Tracks how long transactions take to confirm after they are sent.
+pub struct ConfirmationTimeTracker {
+    max_latency_ms: Option<u64>,
+    latencies: Mutex<SlotWindow<u32>>,
+    last_report: Mutex<Instant>,
+    metrics_file: Option<PathBuf>,
+}
+
+impl ConfirmationTimeTracker {
+    pub fn new(max_latency_ms: Option<u64>, metrics_file: Option<PathBuf>) -> Self {
+        Self {
+            max_latency_ms,
+            latencies: Mutex::new(SlotWindow::new(LATENCY_SAMPLES)),
+            last_report: Mutex::new(Instant::now()),
+            metrics_file,
+        }
+    }
+
+    pub fn record(&self, slot: Slot, sent_at: Instant, confirmed_at: Instant) {
+        let latency_ms = confirmed_at.duration_since(sent_at).as_millis();
+        let mut latencies = self.latencies.lock().expect("failed to lock mutex");
+        latencies.insert(slot, latency_ms.min(u32::MAX as u128) as u32);
+        if let Some(path) = self.metrics_file.as_ref() {
+            if let Err(err) = write_metrics(path, &latencies) {
+                batch_println!(
+                    "Failed to write confirmation latency metrics to {:?}: {}",
+                    path,
+                    err
+                );
+            }
+        }
+
+        let mut last_report = self.last_report.lock().expect("failed to lock mutex");
+        if last_report.elapsed() < REPORT_INTERVAL {
+            return;
+        }
+        *last_report = Instant::now();
+        let p50 = latencies.percentile(50.0).unwrap_or_default();
+        let p99 = latencies.percentile(99.0).unwrap_or_default();
+        batch_println!("Confirmation latency: p50 {}ms, p99 {}ms", p50, p99);
+        if let Some(max_latency_ms) = self.max_latency_ms {
+            if p99 > max_latency_ms as f64 {
+                batch_println!(
+                    "Warning: p99 confirmation latency {}ms exceeds {}ms",
+                    p99,
+                    max_latency_ms
+                );
+            }
+        }
+    }
+}
+
+/// Writes the rolling `miner_confirm_latency_ms` quantiles in the Prometheus text format.
+fn write_metrics(path: &Path, latencies: &SlotWindow<u32>) -> io::Result<()> {
+    let mut text = String::from(
+        "# HELP miner_confirm_latency_ms Rolling time from send until a transaction confirms\n\
+         # TYPE miner_confirm_latency_ms gauge\n",
+    );
+    for quantile in QUANTILES {
+        if let Some(latency) = latencies.percentile(quantile * 100.0) {
+            writeln!(
+                text,
+                "miner_confirm_latency_ms{{quantile=\"{}\"}} {}",
+                quantile, latency
+            )
+            .ok();
+        }
+    }
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, text)?;
+    fs::rename(tmp_path, path)
+}
@@ -0,0 +1,141 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use solana_program::pubkey::Pubkey;
+use solana_sdk::transaction::Transaction;
+
+use crate::annotation::TransactionAnnotation;
+
+#[derive(Clone, Copy, Debug, Default)]
+struct AccountSize {
+    data_len: usize,
+    growth: usize,
+}
+
+/// Predicts the lamports a transaction costs, adding fees on write-locked and newly
+/// allocated account bytes to the signature and prioritization fees charged today.
+/// Both byte fees default to 0, which matches the current fee model.
+#[derive(Default)]
+pub struct TransactionCostModel {
+    write_lock_lamports_per_byte: u64,
+    storage_lamports_per_byte: u64,
+    account_sizes: Mutex<HashMap<Pubkey, AccountSize>>,
+}
+
+impl TransactionCostModel {
+    pub fn new(write_lock_lamports_per_byte: u64, storage_lamports_per_byte: u64) -> Self {
+        Self {
+            write_lock_lamports_per_byte,
+            storage_lamports_per_byte,
+            account_sizes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Records the current data length of an account, remembering how much it grew since
+    /// it was last seen.
+    pub fn observe_account(&self, pubkey: Pubkey, data_len: usize) {
+        let mut sizes = self.account_sizes.lock().expect("failed to lock mutex");
+        let size = sizes.entry(pubkey).or_insert(AccountSize {
+            data_len,
+            growth: 0,
+        });
+        size.growth = data_len.saturating_sub(size.data_len);
+        size.data_len = data_len;
+    }
+
+    /// Returns the predicted cost of `tx` in lamports. Accounts never observed are treated
+    /// as empty.
+    pub fn predict(&self, tx: &Transaction) -> u64 {
+        let fee = TransactionAnnotation::new(tx, None).fee_lamports;
+        let sizes = self.account_sizes.lock().expect("failed to lock mutex");
+        let (locked_bytes, growth_bytes) = tx
+            .message
+            .account_keys
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| tx.message.is_writable(*i))
+            .filter_map(|(_, pubkey)| sizes.get(pubkey))
+            .fold((0u64, 0u64), |(locked, growth), size| {
+                (locked + size.data_len as u64, growth + size.growth as u64)
+            });
+        fee.saturating_add(
+            self.write_lock_lamports_per_byte
+                .saturating_mul(locked_bytes),
+        )
+        .saturating_add(self.storage_lamports_per_byte.saturating_mul(growth_bytes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ore::TREASURY_ADDRESS;
+    use solana_program::{instruction::Instruction, keccak::Hash, pubkey::Pubkey};
+    use solana_sdk::{compute_budget::ComputeBudgetInstruction, transaction::Transaction};
+
+    use super::TransactionCostModel;
+    use crate::{
+        cu_limits::{CU_LIMIT_CLAIM, CU_LIMIT_MINE, CU_LIMIT_RESET},
+        utils::proof_pubkey,
+    };
+
+    const SIGNER: Pubkey = Pubkey::new_from_array([1; 32]);
+    const BENEFICIARY: Pubkey = Pubkey::new_from_array([2; 32]);
+    // One lamport per compute unit
+    const PRICE: u64 = 1_000_000;
+
+    fn budgeted_tx(cu_limit: u32, ix: Instruction) -> Transaction {
+        Transaction::new_with_payer(
+            &[
+                ComputeBudgetInstruction::set_compute_unit_limit(cu_limit),
+                ComputeBudgetInstruction::set_compute_unit_price(PRICE),
+                ix,
+            ],
+            Some(&SIGNER),
+        )
+    }
+
+    fn mine_tx() -> Transaction {
+        let ix = ore::instruction::mine(SIGNER, ore::BUS_ADDRESSES[0], Hash::default().into(), 0);
+        budgeted_tx(CU_LIMIT_MINE, ix)
+    }
+
+    fn claim_tx() -> Transaction {
+        budgeted_tx(
+            CU_LIMIT_CLAIM,
+            ore::instruction::claim(SIGNER, BENEFICIARY, 1),
+        )
+    }
+
+    fn reset_tx() -> Transaction {
+        budgeted_tx(CU_LIMIT_RESET, ore::instruction::reset(SIGNER))
+    }
+
+    #[test]
+    fn predicts_signature_and_prioritization_fees() {
+        let model = TransactionCostModel::default();
+        assert_eq!(model.predict(&mine_tx()), 5_000 + 3_200);
+        assert_eq!(model.predict(&claim_tx()), 5_000 + 11_000);
+        assert_eq!(model.predict(&reset_tx()), 5_000 + 12_200);
+    }
+
+    #[test]
+    fn charges_write_locked_bytes() {
+        let model = TransactionCostModel::new(2, 0);
+        model.observe_account(proof_pubkey(SIGNER), 1_000);
+        model.observe_account(TREASURY_ADDRESS, 500);
+        // Mine only reads the treasury, while claim writes it
+        assert_eq!(model.predict(&mine_tx()), 5_000 + 3_200 + 2 * 1_000);
+        assert_eq!(model.predict(&claim_tx()), 5_000 + 11_000 + 2 * 1_500);
+        assert_eq!(model.predict(&reset_tx()), 5_000 + 12_200 + 2 * 500);
+    }
+
+    #[test]
+    fn charges_growth_since_last_observed() {
+        let model = TransactionCostModel::new(0, 3);
+        model.observe_account(proof_pubkey(SIGNER), 100);
+        assert_eq!(model.predict(&mine_tx()), 5_000 + 3_200);
+        model.observe_account(proof_pubkey(SIGNER), 150);
+        assert_eq!(model.predict(&mine_tx()), 5_000 + 3_200 + 3 * 50);
+        model.observe_account(proof_pubkey(SIGNER), 150);
+        assert_eq!(model.predict(&mine_tx()), 5_000 + 3_200);
+    }
+}
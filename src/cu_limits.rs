@@ -1,3 +1,6 @@
 pub const CU_LIMIT_CLAIM: u32 = 11_000;
 pub const CU_LIMIT_RESET: u32 = 12_200;
 pub const CU_LIMIT_MINE: u32 = 3200;
+// The memo program logs and validates its data as UTF-8, so its cost grows with memo length
+pub const CU_LIMIT_MEMO_BASE: u32 = 1_000;
+pub const CU_LIMIT_MEMO_PER_BYTE: u32 = 20;
@@ -0,0 +1,92 @@
+use std::sync::Mutex;
+
+use solana_sdk::{instruction::InstructionError, transaction::TransactionError};
+
+use crate::lifecycle::batch_println;
+
+// Confirmed transactions per evaluation window
+const TUNE_WINDOW: usize = 100;
+const RAISE_ABOVE_RATE: f64 = 0.05;
+const LOWER_BELOW_RATE: f64 = 0.01;
+const RAISE_STEP: f64 = 0.05;
+const LOWER_STEP: f64 = 0.01;
+
+/// Adjusts the factor applied to simulated compute units based on how often confirmed
+/// transactions run out of compute. The factor never drops below `--cu-overhead-factor`.
+pub struct CuLimitAutoTuner {
+    min_factor: f64,
+    state: Mutex<TunerState>,
+}
+
+struct TunerState {
+    // Samples still to discard while programs warm up
+    warmup: usize,
+    factor: f64,
+    transactions: usize,
+    overruns: usize,
+}
+
+impl CuLimitAutoTuner {
+    pub fn new(factor: f64, warmup: usize) -> Self {
+        let factor = factor.max(1.0);
+        Self {
+            min_factor: factor,
+            state: Mutex::new(TunerState {
+                warmup,
+                factor,
+                transactions: 0,
+                overruns: 0,
+            }),
+        }
+    }
+
+    pub fn factor(&self) -> f64 {
+        self.state.lock().expect("failed to lock mutex").factor
+    }
+
+    /// Records the outcome of a confirmed transaction, re-tuning after every full window.
+    /// The first `warmup` outcomes are discarded, since programs may use more compute on
+    /// their first invocations.
+    pub fn record(&self, err: Option<&TransactionError>) {
+        let mut state = self.state.lock().expect("failed to lock mutex");
+        if state.warmup > 0 {
+            state.warmup -= 1;
+            return;
+        }
+        state.transactions += 1;
+        if matches!(
+            err,
+            Some(TransactionError::InstructionError(
+                _,
+                InstructionError::ComputationalBudgetExceeded
+            ))
+        ) {
+            state.overruns += 1;
+        }
+        if state.transactions < TUNE_WINDOW {
+            return;
+        }
+
+        let rate = state.overruns as f64 / state.transactions as f64;
+        let factor = if rate > RAISE_ABOVE_RATE {
+            state.factor + RAISE_STEP
+        } else if rate < LOWER_BELOW_RATE {
+            (state.factor - LOWER_STEP).max(self.min_factor)
+        } else {
+            state.factor
+        };
+        if factor != state.factor {
+            batch_println!(
+                "Compute unit overrun rate {:.1}%, overhead factor now {:.2}",
+                rate * 100.0,
+                factor
+            );
+        }
+        *state = TunerState {
+            warmup: 0,
+            factor,
+            transactions: 0,
+            overruns: 0,
+        };
+    }
+}
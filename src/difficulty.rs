@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+use solana_sdk::keccak::Hash as KeccakHash;
+
+// Weight of the newest epoch in the difficulty forecast
+const DIFFICULTY_EMA_ALPHA: f64 = 0.3;
+
+/// Forecasts the difficulty of the next epoch from an exponential moving average of the
+/// difficulties seen so far.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct DifficultyPredictor {
+    pub alpha: f64,
+    pub ema: f64,
+}
+
+impl DifficultyPredictor {
+    pub fn new(alpha: f64) -> Self {
+        Self { alpha, ema: 0.0 }
+    }
+
+    pub fn update(&mut self, observed_difficulty: u64) {
+        self.ema = if self.ema == 0.0 {
+            observed_difficulty as f64
+        } else {
+            self.alpha * observed_difficulty as f64 + (1.0 - self.alpha) * self.ema
+        };
+    }
+
+    pub fn predict_next(&self) -> u64 {
+        self.ema.round() as u64
+    }
+}
+
+impl Default for DifficultyPredictor {
+    fn default() -> Self {
+        Self::new(DIFFICULTY_EMA_ALPHA)
+    }
+}
+
+/// The expected number of hashes needed to find one at or below the `difficulty` hash,
+/// estimated from its leading 8 bytes.
+pub fn difficulty_value(difficulty: &KeccakHash) -> u64 {
+    let mut leading = [0u8; 8];
+    leading.copy_from_slice(&difficulty.to_bytes()[..8]);
+    u64::MAX / u64::from_be_bytes(leading).max(1)
+}
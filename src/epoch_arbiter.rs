@@ -0,0 +1,145 @@
+use std::sync::Arc;
+
+use futures::future;
+use solana_program::{instruction::Instruction, pubkey::Pubkey};
+use solana_sdk::signature::{read_keypair_file, Keypair, Signature, Signer};
+use tokio::sync::mpsc;
+
+use crate::{
+    auto_batch::{auto_batch, BatchSplitStrategy},
+    utils::get_proof,
+    Miner,
+};
+
+/// A claim queued by one miner, signed by that miner's keypair.
+pub struct ClaimRequest {
+    pub signer: Arc<Keypair>,
+    pub ix: Instruction,
+}
+
+#[derive(Debug)]
+pub struct ClaimResult {
+    pub miner: Pubkey,
+    pub result: Result<Signature, String>,
+}
+
+/// Collects claims from several miners and submits them together as one batch, packing as
+/// many as fit into each transaction. The claims land or fail together.
+pub struct EpochBoundaryArbiter {
+    sender: mpsc::UnboundedSender<ClaimRequest>,
+    receiver: mpsc::UnboundedReceiver<ClaimRequest>,
+}
+
+impl Default for EpochBoundaryArbiter {
+    fn default() -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        Self { sender, receiver }
+    }
+}
+
+impl EpochBoundaryArbiter {
+    pub fn sender(&self) -> mpsc::UnboundedSender<ClaimRequest> {
+        self.sender.clone()
+    }
+
+    /// Waits for every sender to be dropped, then sends the collected claims with
+    /// `payer` paying the fees.
+    pub async fn submit(self, payer: &Miner) -> Vec<ClaimResult> {
+        let Self {
+            sender,
+            mut receiver,
+        } = self;
+        drop(sender);
+        let mut requests = vec![];
+        while let Some(request) = receiver.recv().await {
+            requests.push(request);
+        }
+        if requests.is_empty() {
+            return vec![];
+        }
+
+        let fee_payer = payer.signer();
+        let ixs: Vec<Instruction> = requests.iter().map(|request| request.ix.clone()).collect();
+        let txs_ixs = match auto_batch(
+            &ixs,
+            &fee_payer.pubkey(),
+            true,
+            BatchSplitStrategy::GreedyFit,
+            &payer.fee_token_reserve(&fee_payer.pubkey()),
+        ) {
+            Ok(txs_ixs) => txs_ixs,
+            Err(err) => {
+                return requests
+                    .iter()
+                    .map(|request| ClaimResult {
+                        miner: request.signer.pubkey(),
+                        result: Err(err.to_string()),
+                    })
+                    .collect();
+            }
+        };
+
+        let cosigners: Vec<Arc<Keypair>> = requests
+            .iter()
+            .map(|request| request.signer.clone())
+            .collect();
+        let tx_lens: Vec<usize> = txs_ixs.iter().map(Vec::len).collect();
+        let batch = payer
+            .send_and_confirm_batch(txs_ixs, &cosigners, true, false)
+            .await
+            .map_err(|err| err.to_string());
+
+        // Batching keeps instructions in order, so each transaction takes the next requests
+        let mut results = vec![];
+        let mut requests = requests.iter();
+        for (index, len) in tx_lens.into_iter().enumerate() {
+            let result = batch
+                .as_ref()
+                .map(|batch| batch.signatures[index])
+                .map_err(Clone::clone);
+            results.extend(requests.by_ref().take(len).map(|request| ClaimResult {
+                miner: request.signer.pubkey(),
+                result: result.clone(),
+            }));
+        }
+        results
+    }
+}
+
+impl Miner {
+    /// Claims the rewards of every keypair in `keypair_paths` in as few transactions as
+    /// possible, paying fees from the configured keypair.
+    pub async fn claim_all(&self, keypair_paths: Vec<String>) {
+        let arbiter = EpochBoundaryArbiter::default();
+        let claims = keypair_paths.into_iter().map(|path| {
+            let sender = arbiter.sender();
+            async move {
+                let signer = match read_keypair_file(&path) {
+                    Ok(signer) => Arc::new(signer),
+                    Err(err) => {
+                        println!("Failed to read keypair {}: {}", path, err);
+                        return;
+                    }
+                };
+                let miner = Miner::new(self.rpc_client.clone(), self.priority_fee, Some(path));
+                let beneficiary = miner.initialize_ata().await;
+                let proof = get_proof(&self.rpc_client, signer.pubkey()).await;
+                let ix =
+                    ore::instruction::claim(signer.pubkey(), beneficiary, proof.claimable_rewards);
+                sender.send(ClaimRequest { signer, ix }).ok();
+            }
+        });
+        future::join_all(claims).await;
+
+        for claim in arbiter.submit(self).await {
+            match claim.result {
+                Ok(sig) => println!(
+                    "Claimed for {}: {}",
+                    claim.miner,
+                    self.signature_display.format(&sig)
+                ),
+                Err(err) => println!("Claim for {} failed: {}", claim.miner, err),
+            }
+        }
+    }
+}
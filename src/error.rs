@@ -0,0 +1,169 @@
+use std::{fmt, time::Duration};
+
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use solana_program::{hash::Hash, pubkey::Pubkey};
+
+#[derive(Debug)]
+pub enum MinerError {
+    SimulationFailed,
+    CircuitBreakerOpen,
+    QueueFull {
+        capacity: usize,
+        submitted: usize,
+    },
+    ConfirmationTimeout(Duration),
+    FinalizationTimeout(Duration),
+    TooManyAccounts {
+        count: usize,
+        max: usize,
+    },
+    BundleStatusUnavailable(String),
+    TransactionTooLarge {
+        size: usize,
+        max: usize,
+    },
+    SignatureVerificationFailed(Pubkey),
+    RetryBudgetExhausted(usize),
+    UnexpectedAccountOwner {
+        account: Pubkey,
+        expected: Pubkey,
+        found: Pubkey,
+    },
+    ProgramError {
+        instruction: u8,
+        code: u32,
+        name: String,
+    },
+    UnknownDiscriminator {
+        index: usize,
+        instruction: String,
+    },
+    InvalidInstructionAccounts {
+        kind: &'static str,
+        instruction: String,
+        count: usize,
+        expected: usize,
+    },
+    AccountNotRentExempt {
+        account: Pubkey,
+        lamports: u64,
+        minimum: u64,
+    },
+    NonceConsumed {
+        new_nonce: Hash,
+    },
+    InvalidBatchSplit {
+        transactions: usize,
+        instructions: usize,
+    },
+}
+
+impl fmt::Display for MinerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MinerError::SimulationFailed => write!(f, "Simulation failed"),
+            MinerError::CircuitBreakerOpen => write!(f, "RPC circuit breaker is open"),
+            MinerError::QueueFull {
+                capacity,
+                submitted,
+            } => write!(
+                f,
+                "Batch of {} transactions exceeds the queue depth of {}",
+                submitted, capacity
+            ),
+            MinerError::ConfirmationTimeout(timeout) => write!(
+                f,
+                "Transaction was not confirmed within {}s",
+                timeout.as_secs()
+            ),
+            MinerError::FinalizationTimeout(timeout) => write!(
+                f,
+                "Transaction was not finalized within {}s",
+                timeout.as_secs()
+            ),
+            MinerError::TooManyAccounts { count, max } => write!(
+                f,
+                "Transaction references {} accounts, more than the limit of {}",
+                count, max
+            ),
+            MinerError::TransactionTooLarge { size, max } => write!(
+                f,
+                "Transaction is {} bytes, more than the limit of {}",
+                size, max
+            ),
+            MinerError::SignatureVerificationFailed(pubkey) => {
+                write!(f, "Signature of {} does not verify", pubkey)
+            }
+            MinerError::RetryBudgetExhausted(total) => {
+                write!(f, "Batch used up its budget of {} retries", total)
+            }
+            MinerError::UnexpectedAccountOwner {
+                account,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Account {} is owned by {}, expected {}",
+                account, found, expected
+            ),
+            MinerError::ProgramError {
+                instruction,
+                code,
+                name,
+            } => write!(
+                f,
+                "Instruction {} failed with program error {} ({})",
+                instruction, code, name
+            ),
+            MinerError::UnknownDiscriminator { index, instruction } => write!(
+                f,
+                "Instruction {} [{}] has a discriminator the Ore program does not define",
+                index, instruction
+            ),
+            MinerError::InvalidInstructionAccounts {
+                kind,
+                instruction,
+                count,
+                expected,
+            } => write!(
+                f,
+                "Ore {} instruction [{}] has {} accounts, expected {} including a signer",
+                kind, instruction, count, expected
+            ),
+            MinerError::AccountNotRentExempt {
+                account,
+                lamports,
+                minimum,
+            } => write!(
+                f,
+                "Account {} holds {} lamports, below the rent-exempt minimum of {}",
+                account, lamports, minimum
+            ),
+            MinerError::NonceConsumed { new_nonce } => write!(
+                f,
+                "Nonce account advanced to {} before the transaction confirmed",
+                new_nonce
+            ),
+            MinerError::InvalidBatchSplit {
+                transactions,
+                instructions,
+            } => write!(
+                f,
+                "Cannot split {} instructions into {} transactions",
+                instructions, transactions
+            ),
+            MinerError::BundleStatusUnavailable(msg) => {
+                write!(f, "Failed to fetch bundle status: {}", msg)
+            }
+        }
+    }
+}
+
+impl From<MinerError> for ClientError {
+    fn from(err: MinerError) -> Self {
+        ClientError {
+            request: None,
+            kind: ClientErrorKind::Custom(err.to_string()),
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use std::{path::Path, str::FromStr};
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde::Deserialize;
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+
+#[derive(Deserialize)]
+struct AccountMetaFile {
+    pubkey: String,
+    is_signer: bool,
+    is_writable: bool,
+}
+
+#[derive(Deserialize)]
+struct InstructionFile {
+    program_id: String,
+    accounts: Vec<AccountMetaFile>,
+    /// Base64 encoded instruction data
+    data: String,
+}
+
+fn parse_pubkey(pubkey: &str) -> Result<Pubkey, String> {
+    Pubkey::from_str(pubkey).map_err(|err| format!("invalid pubkey `{}`: {}", pubkey, err))
+}
+
+/// Loads an instruction set from a JSON file of the form
+/// `[{ "program_id": "...", "accounts": [{ "pubkey": "...", "is_signer": false, "is_writable": true }], "data": "<base64>" }]`.
+pub fn load_instructions(path: &Path) -> Result<Vec<Instruction>, String> {
+    let data = std::fs::read(path).map_err(|err| format!("failed to read {:?}: {}", path, err))?;
+    let ixs: Vec<InstructionFile> = serde_json::from_slice(&data)
+        .map_err(|err| format!("failed to parse {:?}: {}", path, err))?;
+    ixs.into_iter()
+        .map(|ix| {
+            let accounts = ix
+                .accounts
+                .iter()
+                .map(|meta| {
+                    Ok(AccountMeta {
+                        pubkey: parse_pubkey(&meta.pubkey)?,
+                        is_signer: meta.is_signer,
+                        is_writable: meta.is_writable,
+                    })
+                })
+                .collect::<Result<Vec<_>, String>>()?;
+            Ok(Instruction {
+                program_id: parse_pubkey(&ix.program_id)?,
+                accounts,
+                data: BASE64
+                    .decode(&ix.data)
+                    .map_err(|err| format!("invalid instruction data: {}", err))?,
+            })
+        })
+        .collect()
+}
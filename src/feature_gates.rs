@@ -0,0 +1,60 @@
+use std::sync::Arc;
+
+use solana_client::{client_error::Result as ClientResult, nonblocking::rpc_client::RpcClient};
+use solana_program::pubkey::Pubkey;
+use solana_sdk::{feature, feature_set};
+
+/// Reads runtime feature gate accounts to tell which features the cluster has activated.
+pub struct SolanaFeatureGateChecker {
+    rpc_client: Arc<RpcClient>,
+}
+
+impl SolanaFeatureGateChecker {
+    pub fn new(rpc_client: Arc<RpcClient>) -> Self {
+        Self { rpc_client }
+    }
+
+    /// Whether `feature` has been activated. Features never proposed on this cluster
+    /// have no account and count as inactive.
+    pub async fn is_active(&self, feature: Pubkey) -> ClientResult<bool> {
+        let account = self
+            .rpc_client
+            .get_account_with_commitment(&feature, self.rpc_client.commitment())
+            .await?
+            .value;
+        Ok(account
+            .and_then(|account| feature::from_account(&account))
+            .is_some_and(|feature| feature.activated_at.is_some()))
+    }
+
+    /// Whether any node advertises a QUIC TPU address through gossip. QUIC transaction
+    /// ingestion has no runtime feature account, so the contact info is the only signal.
+    pub async fn advertises_tpu_quic(&self) -> ClientResult<bool> {
+        let nodes = self.rpc_client.get_cluster_nodes().await?;
+        Ok(nodes.iter().any(|node| node.tpu_quic.is_some()))
+    }
+}
+
+/// The features optional miner behavior depends on.
+pub struct ClusterFeatures {
+    /// Versioned transactions, needed to send through address lookup tables
+    pub versioned_transactions: bool,
+    /// The compute budget program's compute unit price instruction, needed for priority fees
+    pub compute_unit_price: bool,
+    /// Validators accepting transactions over QUIC, needed to send with `--tpu-quic`
+    pub tpu_quic: bool,
+}
+
+impl ClusterFeatures {
+    pub async fn detect(checker: &SolanaFeatureGateChecker) -> ClientResult<Self> {
+        Ok(Self {
+            versioned_transactions: checker
+                .is_active(feature_set::versioned_tx_message_enabled::id())
+                .await?,
+            compute_unit_price: checker
+                .is_active(feature_set::add_set_compute_unit_price_ix::id())
+                .await?,
+            tpu_quic: checker.advertises_tpu_quic().await?,
+        })
+    }
+}
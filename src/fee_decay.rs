@@ -0,0 +1,61 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::Miner;
+
+/// Raises the priority fee by a fixed step whenever a send has to be retried and lowers
+/// it by the same step after every confirmed transaction, settling near the lowest fee
+/// that still lands, but never below the configured `--priority-fee`.
+///
+/// The fee is signed into a transaction when it is built, so a retry resends the same
+/// transaction at its original fee. A raised fee takes effect from the next transaction
+/// built, such as the next mining submission.
+pub struct PriorityFeeDecayScheduler {
+    fee: AtomicU64,
+    step: u64,
+    min_fee: u64,
+    max_fee: Option<u64>,
+}
+
+impl PriorityFeeDecayScheduler {
+    pub fn new(initial_fee: u64, step: u64, max_fee: Option<u64>) -> Self {
+        Self {
+            fee: AtomicU64::new(initial_fee),
+            step,
+            min_fee: initial_fee,
+            max_fee,
+        }
+    }
+
+    pub fn fee(&self) -> u64 {
+        self.fee.load(Ordering::Relaxed)
+    }
+
+    /// Raises the fee by one step, up to `--max-priority-fee` if set.
+    pub fn raise(&self) {
+        self.fee
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |fee| {
+                let raised = fee.saturating_add(self.step);
+                Some(self.max_fee.map_or(raised, |max_fee| raised.min(max_fee)))
+            })
+            .ok();
+    }
+
+    /// Lowers the fee by one step, down to the fee it started at.
+    pub fn lower(&self) {
+        self.fee
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |fee| {
+                Some(fee.saturating_sub(self.step).max(self.min_fee))
+            })
+            .ok();
+    }
+}
+
+impl Miner {
+    /// The priority fee for the next transaction, as scheduled when `--fee-decay-step` is
+    /// set and fixed otherwise.
+    pub fn scheduled_priority_fee(&self) -> u64 {
+        self.fee_decay
+            .as_ref()
+            .map_or(self.priority_fee, PriorityFeeDecayScheduler::fee)
+    }
+}
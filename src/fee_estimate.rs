@@ -0,0 +1,63 @@
+use solana_client::client_error::Result as ClientResult;
+use solana_program::instruction::Instruction;
+use solana_sdk::{signature::Signer, transaction::Transaction};
+
+use crate::{
+    annotation::{TransactionAnnotation, LAMPORTS_PER_SIGNATURE},
+    transaction::MinerTransaction,
+    utils::get_proof,
+    Miner,
+};
+
+#[derive(Clone, Copy, Debug)]
+pub struct FeeEstimate {
+    pub base_fee_lamports: u64,
+    pub priority_fee_lamports: u64,
+    pub total_fee_lamports: u64,
+    pub compute_units: u64,
+}
+
+impl Miner {
+    /// Simulates the transaction `send_and_confirm` would build from `ixs` and breaks down
+    /// the fees it would pay, without signing or sending it.
+    pub async fn estimate_transaction_fees(
+        &self,
+        ixs: &[Instruction],
+        dynamic_cus: bool,
+    ) -> ClientResult<FeeEstimate> {
+        let payer = self.signer().pubkey();
+        let tx = Transaction::new_with_payer(ixs, Some(&payer));
+        let compute_units = self.simulate_compute_units(&tx, ixs).await?;
+        let tx = if dynamic_cus {
+            Transaction::new_with_payer(&self.budget_instructions(ixs, compute_units), Some(&payer))
+        } else {
+            tx
+        };
+        let total_fee_lamports = TransactionAnnotation::new(&tx, Some(compute_units)).fee_lamports;
+        let base_fee_lamports = LAMPORTS_PER_SIGNATURE * tx.num_required_signatures() as u64;
+        Ok(FeeEstimate {
+            base_fee_lamports,
+            priority_fee_lamports: total_fee_lamports.saturating_sub(base_fee_lamports),
+            total_fee_lamports,
+            compute_units,
+        })
+    }
+
+    /// Prints the fees of claiming all claimable rewards to the signer's token account.
+    pub async fn estimate_fee(&self) {
+        let pubkey = self.signer().pubkey();
+        let beneficiary =
+            spl_associated_token_account::get_associated_token_address(&pubkey, &ore::MINT_ADDRESS);
+        let proof = get_proof(&self.rpc_client, pubkey).await;
+        let ixs = self.claim_ixs(pubkey, beneficiary, proof.claimable_rewards);
+        match self.estimate_transaction_fees(&ixs, false).await {
+            Ok(estimate) => {
+                println!("Compute units: {}", estimate.compute_units);
+                println!("Base fee: {} lamports", estimate.base_fee_lamports);
+                println!("Priority fee: {} lamports", estimate.priority_fee_lamports);
+                println!("Total fee: {} lamports", estimate.total_fee_lamports);
+            }
+            Err(err) => println!("Error: {}", err),
+        }
+    }
+}
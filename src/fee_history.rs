@@ -0,0 +1,65 @@
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::clock::Slot;
+
+use crate::{send_and_confirm::Confirmation, slot_window::SlotWindow, Miner};
+
+const FEE_HISTORY_SAMPLES: usize = 1000;
+// Samples older than this many slots, about an hour, no longer reflect the fee market
+const FEE_HISTORY_SLOTS: Slot = 9000;
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct FeeSample {
+    /// Slot the outcome was observed in
+    #[serde(default)]
+    pub slot: Slot,
+    /// Compute unit price in microlamports
+    pub fee: u64,
+    pub landed: bool,
+}
+
+/// Priority fees paid by recent submissions and whether they landed.
+pub struct PriorityFeeHistory {
+    samples: Mutex<SlotWindow<FeeSample>>,
+}
+
+impl Default for PriorityFeeHistory {
+    fn default() -> Self {
+        Self {
+            samples: Mutex::new(SlotWindow::new(FEE_HISTORY_SAMPLES)),
+        }
+    }
+}
+
+impl PriorityFeeHistory {
+    pub fn record(&self, slot: Slot, fee: u64, landed: bool) {
+        let mut samples = self.samples.lock().expect("failed to lock mutex");
+        samples.evict_before(slot.saturating_sub(FEE_HISTORY_SLOTS));
+        samples.insert(slot, FeeSample { slot, fee, landed });
+    }
+
+    pub fn samples(&self) -> Vec<FeeSample> {
+        let samples = self.samples.lock().expect("failed to lock mutex");
+        samples.values_since(0).copied().collect()
+    }
+
+    pub fn restore(&self, samples: &[FeeSample]) {
+        let mut current = self.samples.lock().expect("failed to lock mutex");
+        *current = SlotWindow::new(FEE_HISTORY_SAMPLES);
+        for sample in samples {
+            current.insert(sample.slot, *sample);
+        }
+    }
+}
+
+impl Miner {
+    /// The slot to record a fee sample at: the slot its transaction confirmed in, or the
+    /// current slot if it did not land.
+    pub async fn fee_sample_slot(&self, confirmation: Option<&Confirmation>) -> Option<Slot> {
+        match confirmation {
+            Some(confirmation) => Some(confirmation.slot),
+            None => self.guarded_rpc(self.rpc_client.get_slot()).await.ok(),
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use std::sync::atomic::Ordering;
+
+use solana_sdk::signature::Signature;
+
+use crate::{lifecycle::batch_println, Miner};
+
+/// Detects transactions charged less than the fee predicted at their compute unit limit,
+/// as happens when a runtime refunds unused compute budget.
+pub struct GasRefundCollector;
+
+impl GasRefundCollector {
+    /// Returns the lamports refunded on a transaction expected to pay `expected_fee`.
+    pub fn refund(expected_fee: u64, charged_fee: u64) -> u64 {
+        expected_fee.saturating_sub(charged_fee)
+    }
+}
+
+impl Miner {
+    pub fn collect_fee_refund(&self, sig: &Signature, expected_fee: u64, charged_fee: u64) {
+        let refund = GasRefundCollector::refund(expected_fee, charged_fee);
+        if refund == 0 {
+            return;
+        }
+        self.metrics
+            .total_fees_refunded
+            .fetch_add(refund, Ordering::Relaxed);
+        batch_println!(
+            "Transaction {} was refunded {} lamports (expected a fee of {}, charged {})",
+            self.signature_display.format(sig),
+            refund,
+            expected_fee,
+            charged_fee
+        );
+    }
+}
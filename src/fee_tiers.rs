@@ -0,0 +1,93 @@
+use crate::{fee_history::FeeSample, Miner};
+
+const FIT_ITERATIONS: usize = 2000;
+const LEARNING_RATE: f64 = 0.1;
+
+/// Logistic model of landing probability as a function of `ln(1 + fee)`.
+pub struct FeeTierSuggester {
+    pub intercept: f64,
+    pub slope: f64,
+    max_fee: u64,
+}
+
+/// Scales a priority fee by `multiplier`, then caps it at `max_fee` if one is set.
+pub fn scale_priority_fee(fee: u64, multiplier: f64, max_fee: Option<u64>) -> u64 {
+    let scaled = (fee as f64 * multiplier.max(0.0))
+        .round()
+        .min(u64::MAX as f64) as u64;
+    max_fee.map_or(scaled, |max_fee| scaled.min(max_fee))
+}
+
+fn sigmoid(z: f64) -> f64 {
+    1.0 / (1.0 + (-z).exp())
+}
+
+impl FeeTierSuggester {
+    /// Fits the model to `samples`. Returns `None` unless at least one sample landed and
+    /// one did not, since the model is undefined otherwise.
+    pub fn fit(samples: &[FeeSample]) -> Option<Self> {
+        if !samples.iter().any(|s| s.landed) || samples.iter().all(|s| s.landed) {
+            return None;
+        }
+
+        // Standardize the feature so gradient descent converges for any fee scale
+        let xs: Vec<f64> = samples.iter().map(|s| (s.fee as f64).ln_1p()).collect();
+        let n = xs.len() as f64;
+        let mean = xs.iter().sum::<f64>() / n;
+        let std = (xs.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n)
+            .sqrt()
+            .max(f64::EPSILON);
+
+        let (mut b0, mut b1) = (0.0, 0.0);
+        for _ in 0..FIT_ITERATIONS {
+            let (mut g0, mut g1) = (0.0, 0.0);
+            for (x, sample) in xs.iter().zip(samples) {
+                let z = (x - mean) / std;
+                let err = sigmoid(b0 + b1 * z) - if sample.landed { 1.0 } else { 0.0 };
+                g0 += err;
+                g1 += err * z;
+            }
+            b0 -= LEARNING_RATE * g0 / n;
+            b1 -= LEARNING_RATE * g1 / n;
+        }
+
+        // Convert back to coefficients on the unstandardized feature
+        Some(Self {
+            intercept: b0 - b1 * mean / std,
+            slope: b1 / std,
+            max_fee: samples.iter().map(|s| s.fee).max().unwrap_or(0),
+        })
+    }
+
+    /// Returns the fee, in microlamports, needed to land with `target_probability`.
+    pub fn suggest(&self, target_probability: f64) -> u64 {
+        // Paying more has not helped so far, so fall back to the highest fee observed
+        if self.slope <= f64::EPSILON {
+            return self.max_fee;
+        }
+        let p = target_probability.clamp(0.01, 0.99);
+        let logit = (p / (1.0 - p)).ln();
+        let x = (logit - self.intercept) / self.slope;
+        x.exp_m1().clamp(0.0, u64::MAX as f64) as u64
+    }
+}
+
+impl Miner {
+    pub fn print_fee_tier_model(&self) {
+        match FeeTierSuggester::fit(&self.fee_history.samples()) {
+            Some(model) => {
+                println!(
+                    "Fee model: intercept {:.4}, slope {:.4}",
+                    model.intercept, model.slope
+                );
+                println!(
+                    "Suggested fees: {} (50%), {} (90%), {} (99%) microlamports",
+                    model.suggest(0.5),
+                    model.suggest(0.9),
+                    model.suggest(0.99)
+                );
+            }
+            None => println!("Fee model: not enough landed and dropped transactions yet"),
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+};
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+
+use crate::{
+    compute_budget::{parse_compute_budget_ix, static_compute_unit_limit, MAX_COMPUTE_UNIT_LIMIT},
+    ix_encoding::{AnchorEncoder, IxDataEncoder},
+    Miner,
+};
+
+const MICROLAMPORTS_PER_LAMPORT: u128 = 1_000_000;
+
+// Estimated cost of Anchor dispatch plus the SPL token transfer the program makes
+const PAY_FEE_COMPUTE_UNITS: u32 = 25_000;
+
+/// Pays each transaction's priority fee in an SPL token through a fee-compression
+/// program, which reimburses the fee payer in SOL.
+///
+/// Experimental: no such program is deployed for Ore, so the instruction layout below is
+/// an assumed Anchor interface, and the conversion uses the fixed `--fee-token-price`
+/// rather than a price oracle.
+pub struct TokenAccountMiner {
+    pub program_id: Pubkey,
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    /// Token base units per SOL, used to convert the priority fee.
+    pub tokens_per_sol: f64,
+}
+
+impl TokenAccountMiner {
+    /// The priority fee of `ixs` in token base units, from their compute unit limit and
+    /// price.
+    pub fn amount(&self, ixs: &[Instruction]) -> u64 {
+        let price = ixs
+            .iter()
+            .find_map(|ix| match parse_compute_budget_ix(ix) {
+                Some(ComputeBudgetInstruction::SetComputeUnitPrice(price)) => Some(price),
+                _ => None,
+            })
+            .unwrap_or(0);
+        let limit = static_compute_unit_limit(ixs);
+        let lamports = (price as u128 * limit as u128).div_ceil(MICROLAMPORTS_PER_LAMPORT) as f64;
+        (lamports * self.tokens_per_sol / LAMPORTS_PER_SOL as f64).ceil() as u64
+    }
+
+    /// The program's `pay_fee_with_token` instruction, assumed to be an Anchor instruction
+    /// taking the amount as its only argument.
+    pub fn pay_fee_ix(&self, payer: &Pubkey, amount: u64) -> Instruction {
+        let data = AnchorEncoder {
+            name: "pay_fee_with_token",
+        }
+        .encode(&amount.to_le_bytes());
+        Instruction {
+            program_id: self.program_id,
+            accounts: vec![
+                AccountMeta::new(*payer, true),
+                AccountMeta::new(self.token_account, false),
+                AccountMeta::new_readonly(self.mint, false),
+                AccountMeta::new_readonly(spl_token::id(), false),
+            ],
+            data,
+        }
+    }
+}
+
+/// Raises any compute unit limit in `ixs` by the cost of the fee payment instruction.
+fn with_payment_compute_units(ixs: &[Instruction]) -> Vec<Instruction> {
+    ixs.iter()
+        .map(|ix| match parse_compute_budget_ix(ix) {
+            Some(ComputeBudgetInstruction::SetComputeUnitLimit(units)) => {
+                ComputeBudgetInstruction::set_compute_unit_limit(
+                    units
+                        .saturating_add(PAY_FEE_COMPUTE_UNITS)
+                        .min(MAX_COMPUTE_UNIT_LIMIT),
+                )
+            }
+            _ => ix.clone(),
+        })
+        .collect()
+}
+
+impl Miner {
+    /// Prepends the fee token payment for `ixs` when `--fee-token-mint` is set. Their
+    /// compute unit limit is raised to cover it, unless `simulated` says it was sized by
+    /// simulating with the payment already included.
+    pub fn with_fee_token_payment(
+        &self,
+        payer: &Pubkey,
+        ixs: &[Instruction],
+        simulated: bool,
+    ) -> Vec<Instruction> {
+        let Some(fee_token) = self.fee_token.as_ref() else {
+            return ixs.to_vec();
+        };
+        let ixs = if simulated {
+            ixs.to_vec()
+        } else {
+            with_payment_compute_units(ixs)
+        };
+        let mut final_ixs = vec![fee_token.pay_fee_ix(payer, fee_token.amount(&ixs))];
+        final_ixs.extend(ixs);
+        final_ixs
+    }
+
+    /// Instructions `with_fee_token_payment` adds to every transaction, for batching to
+    /// leave room for. The amount does not change their size.
+    pub fn fee_token_reserve(&self, payer: &Pubkey) -> Vec<Instruction> {
+        self.fee_token
+            .iter()
+            .map(|fee_token| fee_token.pay_fee_ix(payer, 0))
+            .collect()
+    }
+}
@@ -0,0 +1,53 @@
+use std::time::Duration;
+
+use solana_client::client_error::Result as ClientResult;
+use solana_sdk::{clock::Slot, signature::Signature};
+use solana_transaction_status::TransactionConfirmationStatus;
+
+use crate::{error::MinerError, lifecycle::batch_println, Miner};
+
+// A confirmed block is usually rooted about 32 slots, or 13 seconds, later
+const FINALIZE_DELAY: u64 = 1000;
+const FINALIZE_RETRIES: u64 = 60;
+
+impl Miner {
+    /// Polls a confirmed signature until it reaches finalized commitment, returning the slot
+    /// it was finalized in.
+    pub async fn wait_for_finalized(&self, sig: Signature) -> ClientResult<Slot> {
+        for _ in 0..FINALIZE_RETRIES {
+            let statuses = self
+                .guarded_rpc(self.read_client().get_signature_statuses(&[sig]))
+                .await?;
+            if let Some(status) = statuses.value.into_iter().flatten().next() {
+                if matches!(
+                    status.confirmation_status,
+                    Some(TransactionConfirmationStatus::Finalized)
+                ) {
+                    return Ok(status.slot);
+                }
+            }
+            tokio::time::sleep(Duration::from_millis(FINALIZE_DELAY)).await;
+        }
+        Err(MinerError::FinalizationTimeout(Duration::from_millis(
+            FINALIZE_DELAY * FINALIZE_RETRIES,
+        ))
+        .into())
+    }
+
+    /// Waits for every transaction of a confirmed batch to finalize, when `--wait-finalized`
+    /// is set.
+    pub async fn wait_for_batch_finalized(&self, sigs: &[Signature]) -> ClientResult<()> {
+        if !self.wait_finalized {
+            return Ok(());
+        }
+        for sig in sigs.iter().copied() {
+            let slot = self.wait_for_finalized(sig).await?;
+            batch_println!(
+                "Transaction {} finalized in slot {}",
+                self.signature_display.format(&sig),
+                slot
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,159 @@
+use std::time::Instant;
+
+use futures::future::join_all;
+use solana_client::client_error::{ClientError, ClientErrorKind, Result as ClientResult};
+use solana_program::instruction::Instruction;
+use solana_sdk::{
+    compute_budget::ComputeBudgetInstruction,
+    signature::{Signature, Signer},
+    transaction::Transaction,
+};
+
+use crate::{
+    lifecycle::{batch_println, BatchContext},
+    send_and_confirm::BatchResult,
+    wallet_mux::sign_with_payer,
+    Miner,
+};
+
+/// `ixs` with a compute unit price of `fee` prepended.
+fn with_compute_unit_price(ixs: &[Instruction], fee: u64) -> Vec<Instruction> {
+    let mut final_ixs = vec![ComputeBudgetInstruction::set_compute_unit_price(fee)];
+    final_ixs.extend_from_slice(ixs);
+    final_ixs
+}
+
+impl Miner {
+    /// Submits one copy of the transaction per priority fee tier and waits for the first
+    /// to confirm. Each copy has its own signature, so the others can still be included
+    /// after it, but they fail once the winner has used the solution and still pay their
+    /// fees. They are reported as skipped and no longer rebroadcast.
+    pub async fn send_gas_auction(
+        &self,
+        ixs: &[Instruction],
+        tiers: &[u64],
+    ) -> ClientResult<Signature> {
+        let context = self.batch_context();
+        context
+            .scope(self.run_gas_auction(ixs, tiers, &context))
+            .await
+    }
+
+    async fn run_gas_auction(
+        &self,
+        ixs: &[Instruction],
+        tiers: &[u64],
+        context: &BatchContext,
+    ) -> ClientResult<Signature> {
+        self.check_queue_depth(tiers.len())?;
+        let signer = self.signer();
+        // The tiers differ only in their compute unit price
+        let tiers_ixs: Vec<Vec<Instruction>> = tiers
+            .iter()
+            .map(|fee| with_compute_unit_price(ixs, *fee))
+            .collect();
+        self.warm_account_cache(signer.pubkey(), &tiers_ixs).await?;
+        // One payer for every tier, chosen for the highest bid so it can afford any of them
+        let top_bid = tiers.iter().max().copied().unwrap_or_default();
+        let (payer, payer_pubkey) =
+            self.select_fee_payer(&signer, &with_compute_unit_price(ixs, top_bid))?;
+        let blockhash = self.latest_blockhash().await?;
+        self.log_blockhash(&blockhash);
+
+        // Built like any other transaction, and all before signing, so one bad tier sends
+        // no bids at all
+        let mut txs: Vec<(u64, Transaction)> = Vec::with_capacity(tiers.len());
+        for (fee, tier_ixs) in tiers.iter().zip(tiers_ixs.iter()) {
+            let (tx, _) = self
+                .build_transaction(&payer_pubkey, tier_ixs, false)
+                .await?;
+            txs.push((*fee, tx));
+        }
+
+        // Sign every tier up front so they go out as close together as possible
+        for (_, tx) in txs.iter_mut() {
+            match payer.clone() {
+                Some(payer) => sign_with_payer(tx, payer, &signer, blockhash.hash)?,
+                None => tx.sign(&[&signer], blockhash.hash),
+            }
+        }
+        let results = join_all(txs.iter().enumerate().map(|(index, (_, tx))| {
+            self.send_signed(
+                tx,
+                blockhash.last_valid_block_height,
+                None,
+                true,
+                context.tx(index),
+            )
+        }))
+        .await;
+        let sent_at = Instant::now();
+
+        let mut bids = Vec::new();
+        let mut bid_fees = Vec::new();
+        let mut annotations = Vec::new();
+        for ((fee, _), res) in txs.iter().zip(results) {
+            match res {
+                Ok((sig, annotation)) => {
+                    batch_println!(
+                        "Gas auction bid at {} microlamports: {}",
+                        fee,
+                        self.signature_display.format(&sig)
+                    );
+                    bids.push(sig);
+                    bid_fees.push(*fee);
+                    annotations.push(annotation);
+                }
+                Err(err) => {
+                    batch_println!("Gas auction bid at {} microlamports failed: {:?}", fee, err)
+                }
+            }
+        }
+
+        if bids.is_empty() {
+            return Err(ClientError {
+                request: None,
+                kind: ClientErrorKind::Custom("No gas auction bid was accepted".into()),
+            });
+        }
+        let confirmed = self.confirm_any(&bids, sent_at).await;
+        let winner = confirmed
+            .as_ref()
+            .ok()
+            .copied()
+            .flatten()
+            .map(|confirmation| confirmation.signature);
+        let confirmation = confirmed.as_ref().ok().and_then(Option::as_ref);
+        if let Some(slot) = self.fee_sample_slot(confirmation).await {
+            for (fee, sig) in bid_fees.iter().zip(bids.iter()) {
+                self.fee_history.record(slot, *fee, winner == Some(*sig));
+            }
+        }
+        confirmed?;
+        match winner {
+            Some(winner) => {
+                if let Some(limit) = self.pending_signatures.as_ref() {
+                    limit.resolve(&winner);
+                }
+                for sig in bids.iter().filter(|sig| winner.ne(sig)) {
+                    if let Some(rebroadcaster) = self.rebroadcaster.as_ref() {
+                        rebroadcaster.cancel(sig);
+                    }
+                    batch_println!("Skipped: {}", self.signature_display.format(sig));
+                }
+                let mut batch = BatchResult::new(context.group_id);
+                for (sig, annotation) in bids.into_iter().zip(annotations) {
+                    if sig == winner {
+                        batch.push((sig, annotation));
+                    }
+                }
+                self.finish_batch(&batch, false).await?;
+                Ok(winner)
+            }
+            None => Err(ClientError {
+                request: None,
+                kind: ClientErrorKind::Custom("No gas auction bid landed".into()),
+            }),
+        }
+    }
+}
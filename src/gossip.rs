@@ -0,0 +1,92 @@
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use solana_client::{client_error::Result as ClientResult, nonblocking::rpc_client::RpcClient};
+use solana_program::pubkey::Pubkey;
+
+use crate::Miner;
+
+// Leaders to look up with the leaders command
+const UPCOMING_LEADERS: u64 = 16;
+
+// How often contact info is re-read, to pick up validators that join or move
+const GOSSIP_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Tracks the QUIC TPU address each validator advertises through gossip. Contact info is
+/// read from the RPC node's view of the gossip network with `getClusterNodes`.
+#[derive(Default)]
+pub struct GossipListener {
+    tpus: RwLock<HashMap<Pubkey, SocketAddr>>,
+}
+
+impl GossipListener {
+    /// Replaces the known TPU addresses with the RPC node's current gossip contact info.
+    pub async fn refresh(&self, client: &RpcClient) -> ClientResult<usize> {
+        let nodes = client.get_cluster_nodes().await?;
+        let tpus: HashMap<Pubkey, SocketAddr> = nodes
+            .into_iter()
+            .filter_map(|node| Some((node.pubkey.parse().ok()?, node.tpu_quic?)))
+            .collect();
+        let count = tpus.len();
+        *self.tpus.write().expect("failed to lock rwlock") = tpus;
+        Ok(count)
+    }
+
+    /// Refreshes the TPU addresses in the background every `GOSSIP_REFRESH_INTERVAL`.
+    pub fn spawn_refresh(self: &Arc<Self>, client: Arc<RpcClient>) {
+        let listener = self.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(GOSSIP_REFRESH_INTERVAL).await;
+                if let Err(err) = listener.refresh(&client).await {
+                    println!("Failed to refresh gossip contact info: {}", err);
+                }
+            }
+        });
+    }
+
+    pub fn tpu_for_leader(&self, leader: Pubkey) -> Option<SocketAddr> {
+        self.tpus
+            .read()
+            .expect("failed to lock rwlock")
+            .get(&leader)
+            .copied()
+    }
+}
+
+impl Miner {
+    pub async fn leaders(&self) {
+        let listener = GossipListener::default();
+        if let Err(err) = listener.refresh(&self.rpc_client).await {
+            println!("Error: {}", err);
+            return;
+        }
+        let slot = match self.guarded_rpc(self.rpc_client.get_slot()).await {
+            Ok(slot) => slot,
+            Err(err) => {
+                println!("Error: {}", err);
+                return;
+            }
+        };
+        let leaders = match self
+            .guarded_rpc(self.rpc_client.get_slot_leaders(slot, UPCOMING_LEADERS))
+            .await
+        {
+            Ok(leaders) => leaders,
+            Err(err) => {
+                println!("Error: {}", err);
+                return;
+            }
+        };
+        for (offset, leader) in leaders.into_iter().enumerate() {
+            let tpu = listener
+                .tpu_for_leader(leader)
+                .map_or("unknown".into(), |tpu| tpu.to_string());
+            println!("Slot {}: {} (TPU {})", slot + offset as u64, leader, tpu);
+        }
+    }
+}
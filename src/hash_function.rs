@@ -0,0 +1,57 @@
+use clap::ValueEnum;
+use sha3::{Digest, Sha3_256};
+use solana_program::{hash, keccak, pubkey::Pubkey};
+
+/// A hash the nonce search can use as its proof of work.
+pub trait HashFunction {
+    fn hash(data: &[u8]) -> [u8; 32];
+}
+
+pub struct Sha256;
+
+impl HashFunction for Sha256 {
+    fn hash(data: &[u8]) -> [u8; 32] {
+        hash::hash(data).to_bytes()
+    }
+}
+
+pub struct Sha3;
+
+impl HashFunction for Sha3 {
+    fn hash(data: &[u8]) -> [u8; 32] {
+        Sha3_256::digest(data).into()
+    }
+}
+
+/// The hash the Ore program verifies today.
+pub struct Keccak;
+
+impl HashFunction for Keccak {
+    fn hash(data: &[u8]) -> [u8; 32] {
+        keccak::hash(data).to_bytes()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+pub enum HashAlgorithm {
+    Sha256,
+    Sha3,
+    #[default]
+    Keccak,
+}
+
+impl HashAlgorithm {
+    /// Hashes a candidate solution: the current challenge, then the miner's pubkey, then
+    /// the nonce in little-endian bytes.
+    pub fn hash_nonce(self, challenge: &[u8; 32], pubkey: &Pubkey, nonce: u64) -> [u8; 32] {
+        let mut data = [0u8; 72];
+        data[..32].copy_from_slice(challenge);
+        data[32..64].copy_from_slice(pubkey.as_ref());
+        data[64..].copy_from_slice(&nonce.to_le_bytes());
+        match self {
+            Self::Sha256 => Sha256::hash(&data),
+            Self::Sha3 => Sha3::hash(&data),
+            Self::Keccak => Keccak::hash(&data),
+        }
+    }
+}
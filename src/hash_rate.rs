@@ -0,0 +1,46 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+/// Counts the hashes computed by one mining thread.
+#[derive(Clone)]
+pub struct HashRateReporter {
+    pub thread_id: usize,
+    counter: Arc<AtomicU64>,
+}
+
+impl HashRateReporter {
+    pub fn new(thread_id: usize) -> Self {
+        Self {
+            thread_id,
+            counter: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// One reporter per mining thread, indexed by thread.
+    pub fn for_threads(threads: usize) -> Vec<Self> {
+        (0..threads).map(Self::new).collect()
+    }
+
+    pub fn record_hash(&self) {
+        self.counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Starts counting from zero, at the start of a new search.
+    pub fn reset(&self) {
+        self.counter.store(0, Ordering::Relaxed);
+    }
+
+    /// Hashes per second over the `elapsed` since the last reset.
+    pub fn rate_per_second(&self, elapsed: Duration) -> f64 {
+        let secs = elapsed.as_secs_f64();
+        if secs == 0.0 {
+            return 0.0;
+        }
+        self.counter.load(Ordering::Relaxed) as f64 / secs
+    }
+}
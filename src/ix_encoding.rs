@@ -0,0 +1,63 @@
+use clap::ValueEnum;
+use solana_program::hash::hash;
+
+/// Encodes the data of instructions built by the miner for a program version.
+pub trait IxDataEncoder {
+    fn encode(&self, raw: &[u8]) -> Vec<u8>;
+}
+
+/// Leaves instruction data as the program expects it today.
+pub struct RawEncoder;
+
+impl IxDataEncoder for RawEncoder {
+    fn encode(&self, raw: &[u8]) -> Vec<u8> {
+        raw.to_vec()
+    }
+}
+
+/// Wraps instruction data in a borsh struct with a single `Vec<u8>` field, which borsh
+/// writes as a little-endian u32 length followed by the bytes.
+pub struct BorshEncoder;
+
+impl IxDataEncoder for BorshEncoder {
+    fn encode(&self, raw: &[u8]) -> Vec<u8> {
+        let mut data = Vec::with_capacity(4 + raw.len());
+        data.extend_from_slice(&(raw.len() as u32).to_le_bytes());
+        data.extend_from_slice(raw);
+        data
+    }
+}
+
+/// Prepends the 8-byte Anchor discriminator of the instruction, the first bytes of
+/// `sha256("global:<name>")`.
+pub struct AnchorEncoder {
+    pub name: &'static str,
+}
+
+impl IxDataEncoder for AnchorEncoder {
+    fn encode(&self, raw: &[u8]) -> Vec<u8> {
+        let preimage = format!("global:{}", self.name);
+        let mut data = hash(preimage.as_bytes()).to_bytes()[..8].to_vec();
+        data.extend_from_slice(raw);
+        data
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+pub enum InstructionEncoding {
+    #[default]
+    Raw,
+    Borsh,
+    Anchor,
+}
+
+impl InstructionEncoding {
+    /// Returns the encoder for an instruction named `name` in the program's interface.
+    pub fn encoder(self, name: &'static str) -> Box<dyn IxDataEncoder> {
+        match self {
+            Self::Raw => Box::new(RawEncoder),
+            Self::Borsh => Box::new(BorshEncoder),
+            Self::Anchor => Box::new(AnchorEncoder { name }),
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use clap::ValueEnum;
+use solana_program::{hash::hash, instruction::Instruction};
+
+use crate::{error::MinerError, utils::encode_instruction_compact};
+
+/// How the Ore program identifies its instructions.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum ProgramType {
+    /// An 8-byte `sha256("global:<name>")` prefix
+    Anchor,
+    /// A single leading byte, as the Ore program does today
+    Raw,
+}
+
+struct OreInstructionSpec {
+    discriminant: u8,
+    name: &'static str,
+    accounts: usize,
+}
+
+// Accounts passed by the instruction builders in the Ore program crate
+const ORE_INSTRUCTIONS: [OreInstructionSpec; 7] = [
+    OreInstructionSpec {
+        discriminant: 0,
+        name: "reset",
+        accounts: 13,
+    },
+    OreInstructionSpec {
+        discriminant: 1,
+        name: "register",
+        accounts: 3,
+    },
+    OreInstructionSpec {
+        discriminant: 2,
+        name: "mine",
+        accounts: 5,
+    },
+    OreInstructionSpec {
+        discriminant: 3,
+        name: "claim",
+        accounts: 6,
+    },
+    OreInstructionSpec {
+        discriminant: 100,
+        name: "initialize",
+        accounts: 18,
+    },
+    OreInstructionSpec {
+        discriminant: 101,
+        name: "update_admin",
+        accounts: 2,
+    },
+    OreInstructionSpec {
+        discriminant: 102,
+        name: "update_difficulty",
+        accounts: 2,
+    },
+];
+
+fn anchor_discriminator(name: &str) -> [u8; 8] {
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&hash(format!("global:{}", name).as_bytes()).to_bytes()[..8]);
+    discriminator
+}
+
+/// Checks Ore instructions against the program's interface before they are sent.
+pub struct InstructionValidator {
+    pub program_type: ProgramType,
+    pub check_discriminator: bool,
+}
+
+impl InstructionValidator {
+    fn spec(&self, data: &[u8]) -> Option<&'static OreInstructionSpec> {
+        ORE_INSTRUCTIONS
+            .iter()
+            .find(|spec| match self.program_type {
+                ProgramType::Anchor => data.starts_with(&anchor_discriminator(spec.name)),
+                ProgramType::Raw => data.first() == Some(&spec.discriminant),
+            })
+    }
+
+    /// Fails if an Ore instruction has an unknown discriminator, unless discriminator
+    /// checks are skipped, or lacks accounts or a signer its kind requires.
+    pub fn validate(&self, ixs: &[Instruction]) -> Result<(), MinerError> {
+        for (index, ix) in ixs.iter().enumerate() {
+            if ix.program_id != ore::ID {
+                continue;
+            }
+            let Some(spec) = self.spec(&ix.data) else {
+                if self.check_discriminator {
+                    return Err(MinerError::UnknownDiscriminator {
+                        index,
+                        instruction: encode_instruction_compact(ix),
+                    });
+                }
+                continue;
+            };
+            if ix.accounts.len() < spec.accounts || !ix.accounts.iter().any(|meta| meta.is_signer) {
+                return Err(MinerError::InvalidInstructionAccounts {
+                    kind: spec.name,
+                    instruction: encode_instruction_compact(ix),
+                    count: ix.accounts.len(),
+                    expected: spec.accounts,
+                });
+            }
+        }
+        Ok(())
+    }
+}
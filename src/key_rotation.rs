@@ -0,0 +1,185 @@
+use std::{
+    fs, io,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use solana_client::client_error::Result as ClientResult;
+use solana_program::system_instruction;
+use solana_sdk::{
+    signature::{read_keypair_file, write_keypair_file, Keypair, Signer},
+    transaction::Transaction,
+};
+
+use crate::{
+    annotation::LAMPORTS_PER_SIGNATURE,
+    lifecycle::batch_println,
+    rotation_log::{KeyRotationLog, RotationTrigger},
+    Miner,
+};
+
+// The fee payer used until the first rotation
+const INITIAL_FEE_PAYER_FILE: &str = "fee-payer.json";
+
+struct RotationState {
+    payer: Arc<Keypair>,
+    rotated_at: Instant,
+    // Unset until the first batch, since restoring a checkpoint can change the count
+    confirmed_at_rotation: Option<u64>,
+}
+
+/// Pays transaction fees from a keypair that is replaced every `--rotate-keypair-every`
+/// confirmed transactions or `--rotate-keypair-interval`, whichever comes first. The
+/// balance moves to the new keypair and each rotation is appended to the rotation log.
+/// The mining signer, which owns the proof, never rotates.
+pub struct KeyRotator {
+    dir: PathBuf,
+    log: KeyRotationLog,
+    every: Option<u64>,
+    interval: Option<Duration>,
+    state: Mutex<RotationState>,
+    rotating: AtomicBool,
+}
+
+fn keypair_error(err: impl ToString) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, err.to_string())
+}
+
+impl KeyRotator {
+    /// Resumes from the fee payer the rotation log last rotated to, or the initial fee
+    /// payer in `dir`, creating it if this is the first run.
+    pub fn open(
+        dir: PathBuf,
+        log: KeyRotationLog,
+        every: Option<u64>,
+        interval: Option<Duration>,
+    ) -> io::Result<Self> {
+        fs::create_dir_all(&dir)?;
+        let path = match log.last_pubkey()? {
+            Some(pubkey) => dir.join(format!("{}.json", pubkey)),
+            None => dir.join(INITIAL_FEE_PAYER_FILE),
+        };
+        let payer = match read_keypair_file(&path) {
+            Ok(payer) => payer,
+            Err(_) if !path.exists() => {
+                let payer = Keypair::new();
+                write_keypair_file(&payer, &path).map_err(keypair_error)?;
+                payer
+            }
+            Err(err) => return Err(keypair_error(err)),
+        };
+        Ok(Self {
+            dir,
+            log,
+            every,
+            interval,
+            state: Mutex::new(RotationState {
+                payer: Arc::new(payer),
+                rotated_at: Instant::now(),
+                confirmed_at_rotation: None,
+            }),
+            rotating: AtomicBool::new(false),
+        })
+    }
+
+    pub fn current(&self) -> Arc<Keypair> {
+        self.state
+            .lock()
+            .expect("failed to lock mutex")
+            .payer
+            .clone()
+    }
+
+    /// The trigger of a rotation that is due, given the transactions confirmed so far.
+    fn due(&self, confirmed: u64) -> Option<RotationTrigger> {
+        let mut state = self.state.lock().expect("failed to lock mutex");
+        let confirmed_at_rotation = *state.confirmed_at_rotation.get_or_insert(confirmed);
+        if self
+            .every
+            .is_some_and(|every| confirmed.saturating_sub(confirmed_at_rotation) >= every)
+        {
+            return Some(RotationTrigger::Count);
+        }
+        if self
+            .interval
+            .is_some_and(|interval| state.rotated_at.elapsed() >= interval)
+        {
+            return Some(RotationTrigger::Time);
+        }
+        None
+    }
+}
+
+impl Miner {
+    /// Rotates the fee payer when `--rotate-keypair-every` or `--rotate-keypair-interval`
+    /// says it is due. Failures are logged and retried after the next batch.
+    pub async fn rotate_fee_payer_if_due(&self) {
+        let Some(rotator) = self.key_rotator.as_ref() else {
+            return;
+        };
+        let confirmed = self.metrics.transactions_confirmed.load(Ordering::Relaxed);
+        let Some(trigger) = rotator.due(confirmed) else {
+            return;
+        };
+        // Batches finishing together must not move the balance twice
+        if rotator.rotating.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        if let Err(err) = self.rotate_fee_payer(rotator, trigger, confirmed).await {
+            batch_println!("Failed to rotate fee payer: {}", err);
+        }
+        rotator.rotating.store(false, Ordering::Release);
+    }
+
+    async fn rotate_fee_payer(
+        &self,
+        rotator: &KeyRotator,
+        trigger: RotationTrigger,
+        confirmed: u64,
+    ) -> ClientResult<()> {
+        let old = rotator.current();
+        let new = Keypair::new();
+        // Saved before any funds move, so the new keypair is never lost
+        write_keypair_file(&new, rotator.dir.join(format!("{}.json", new.pubkey())))
+            .map_err(keypair_error)?;
+
+        let client = self.rpc_client.clone();
+        let balance = self.guarded_rpc(client.get_balance(&old.pubkey())).await?;
+        let amount = balance.saturating_sub(LAMPORTS_PER_SIGNATURE);
+        if amount > 0 {
+            let blockhash = self.latest_blockhash().await?;
+            let tx = Transaction::new_signed_with_payer(
+                &[system_instruction::transfer(
+                    &old.pubkey(),
+                    &new.pubkey(),
+                    amount,
+                )],
+                Some(&old.pubkey()),
+                &[old.as_ref()],
+                blockhash.hash,
+            );
+            self.guarded_rpc(client.send_and_confirm_transaction(&tx))
+                .await?;
+        }
+
+        let result = rotator
+            .log
+            .append(old.pubkey(), new.pubkey(), trigger, amount);
+        *rotator.state.lock().expect("failed to lock mutex") = RotationState {
+            payer: Arc::new(new.insecure_clone()),
+            rotated_at: Instant::now(),
+            confirmed_at_rotation: Some(confirmed),
+        };
+        batch_println!(
+            "Rotated fee payer from {} to {}, moving {} lamports",
+            old.pubkey(),
+            new.pubkey(),
+            amount
+        );
+        Ok(result?)
+    }
+}
@@ -0,0 +1,92 @@
+use std::{fmt::Display, future::Future, time::Instant};
+
+use uuid::Uuid;
+
+use crate::{retry_budget::RetryBudget, Miner};
+
+tokio::task_local! {
+    static GROUP_ID: Uuid;
+}
+
+/// `println!`, tagged with the group id of the batch the current task is sending.
+macro_rules! batch_println {
+    ($($arg:tt)*) => {
+        println!(
+            "{}",
+            $crate::lifecycle::TransactionGrouper::tag(format_args!($($arg)*))
+        )
+    };
+}
+pub(crate) use batch_println;
+
+/// Assigns each batch a group id, carried by the log lines, metrics, and annotations of
+/// all its transactions, so one mining cycle can be followed across batches and miners.
+pub struct TransactionGrouper;
+
+impl TransactionGrouper {
+    pub fn assign() -> Uuid {
+        Uuid::new_v4()
+    }
+
+    /// The group id of the batch the current task is sending, if any.
+    pub fn current() -> Option<Uuid> {
+        GROUP_ID.try_with(|group_id| *group_id).ok()
+    }
+
+    /// Appends the current group id to `message`, when a batch is being sent.
+    pub fn tag(message: impl Display) -> String {
+        match Self::current() {
+            Some(group_id) => format!("{} [group {}]", message, group_id),
+            None => message.to_string(),
+        }
+    }
+}
+
+/// State shared by the transactions of one batch while they are sent.
+pub struct BatchContext {
+    pub group_id: Uuid,
+    pub started_at: Instant,
+    pub retry_budget: Option<RetryBudget>,
+}
+
+/// Where a transaction sits within its batch, for retry accounting and lifecycle events.
+#[derive(Clone, Copy)]
+pub struct TxContext<'a> {
+    pub batch: &'a BatchContext,
+    pub index: usize,
+}
+
+impl BatchContext {
+    pub fn tx(&self, index: usize) -> TxContext<'_> {
+        TxContext { batch: self, index }
+    }
+
+    /// Runs `future` as part of this batch, so everything it logs carries the group id.
+    pub async fn scope<F: Future>(&self, future: F) -> F::Output {
+        GROUP_ID.scope(self.group_id, future).await
+    }
+}
+
+impl TxContext<'_> {
+    /// Emits a debug event for a send/confirm state transition, shown with
+    /// `--send-and-confirm-verbose`.
+    pub fn event(&self, event: &str) {
+        tracing::debug!(
+            group_id = %self.batch.group_id,
+            tx_index = self.index,
+            elapsed_ms = self.batch.started_at.elapsed().as_millis() as u64,
+            "{}",
+            event
+        );
+    }
+}
+
+impl Miner {
+    pub fn batch_context(&self) -> BatchContext {
+        BatchContext {
+            group_id: TransactionGrouper::assign(),
+            started_at: Instant::now(),
+            retry_budget: self.retry_budget(),
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use solana_client::{
+    client_error::{ClientError, ClientErrorKind, Result as ClientResult},
+    rpc_request::MAX_MULTIPLE_ACCOUNTS,
+};
+use solana_program::address_lookup_table::{state::AddressLookupTable, AddressLookupTableAccount};
+
+use crate::Miner;
+
+impl Miner {
+    /// Fetches the address lookup tables passed with `--lookup-table`.
+    pub async fn load_lookup_tables(&self) -> ClientResult<Vec<AddressLookupTableAccount>> {
+        let client = self.rpc_client.clone();
+        let mut lookup_tables = Vec::new();
+        for chunk in self.lookup_tables.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            let accounts = self
+                .guarded_rpc(client.get_multiple_accounts(chunk))
+                .await?;
+            for (key, account) in chunk.iter().zip(accounts) {
+                let table = account
+                    .as_ref()
+                    .and_then(|account| AddressLookupTable::deserialize(&account.data).ok())
+                    .ok_or_else(|| ClientError {
+                        request: None,
+                        kind: ClientErrorKind::Custom(format!(
+                            "Address lookup table {} not found",
+                            key
+                        )),
+                    })?;
+                lookup_tables.push(AddressLookupTableAccount {
+                    key: *key,
+                    addresses: table.addresses.to_vec(),
+                });
+            }
+        }
+        Ok(lookup_tables)
+    }
+}
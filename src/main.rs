@@ -1,71 +1,1031 @@
+mod account_cache;
+mod account_diff;
+mod annotation;
+mod auto_batch;
+mod auto_restart;
 mod balance;
+mod balance_alert;
+mod blockhash;
+mod bundle_status;
 mod busses;
+mod checkpoint;
+mod circuit_breaker;
 mod claim;
+mod compute_budget;
+mod confirmation_strategy;
+mod confirmation_tracker;
+mod cost_model;
 mod cu_limits;
+mod cu_tuner;
+mod difficulty;
+mod epoch_arbiter;
+mod error;
+mod fallback;
+mod feature_gates;
+mod fee_decay;
+mod fee_estimate;
+mod fee_history;
+mod fee_refunds;
+mod fee_tiers;
+mod fee_token;
+mod finalization;
+mod gas_auction;
+mod gossip;
+mod hash_function;
+mod hash_rate;
 #[cfg(feature = "admin")]
 mod initialize;
+mod ix_encoding;
+mod ix_validation;
+mod key_rotation;
+mod lifecycle;
+mod lookup_tables;
+mod memo;
+mod mempool_monitor;
+mod metrics;
 mod mine;
+mod nonce_monitor;
+mod owner_check;
+mod peers;
+mod pending;
+mod profitability;
+mod program_errors;
+mod program_logs;
+mod rebroadcast;
 mod register;
+mod rent_check;
+mod retry_budget;
 mod rewards;
+mod rotation_log;
+#[cfg(feature = "verbose-rpc")]
+mod rpc_log;
+mod rpc_pool;
+mod rpc_selector;
 mod send_and_confirm;
+mod simulate;
+mod slot_pacemaker;
+mod slot_race;
+mod slot_window;
+mod throttle;
+mod tip_pool;
+mod tpu;
+mod transaction;
 mod treasury;
 #[cfg(feature = "admin")]
 mod update_admin;
 #[cfg(feature = "admin")]
 mod update_difficulty;
 mod utils;
+mod wallet_mux;
 
-use std::sync::Arc;
+use std::{
+    net::ToSocketAddrs,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use account_cache::AccountCache;
+use auto_batch::{parse_batch_split_strategy, BatchSplitStrategy};
+use auto_restart::AutoRestarter;
+use balance_alert::BalanceAlert;
+use blockhash::BlockhashCache;
+use checkpoint::Checkpointer;
+use circuit_breaker::RpcCircuitBreaker;
+use clap::{command, Parser, Subcommand};
+use confirmation_strategy::ConfirmationStrategy;
+use confirmation_tracker::ConfirmationTimeTracker;
+use cost_model::TransactionCostModel;
+use cu_tuner::CuLimitAutoTuner;
+use difficulty::DifficultyPredictor;
+use fallback::load_instructions;
+use feature_gates::{ClusterFeatures, SolanaFeatureGateChecker};
+use fee_decay::PriorityFeeDecayScheduler;
+use fee_history::PriorityFeeHistory;
+use fee_tiers::scale_priority_fee;
+use fee_token::TokenAccountMiner;
+use hash_function::HashAlgorithm;
+use hash_rate::HashRateReporter;
+use ix_encoding::InstructionEncoding;
+use ix_validation::{InstructionValidator, ProgramType};
+use key_rotation::KeyRotator;
+use memo::MemoEncoding;
+use mempool_monitor::TransactionMempoolMonitor;
+use metrics::Metrics;
+use mine::NonceRange;
+use pending::PendingSignatureLimit;
+use profitability::ProfitabilityFilter;
+use program_errors::ProgramErrorDecoder;
+use rebroadcast::TransactionRebroadcaster;
+use rotation_log::KeyRotationLog;
+use rpc_pool::RpcPool;
+use rpc_selector::MultiRegionRpcSelector;
+use send_and_confirm::{SendEncoding, MAX_TX_ACCOUNTS};
+use simulate::{
+    parse_balance_override, parse_program_override, BalanceOverride, ProgramOverride,
+    SimulateCommitment,
+};
+use slot_pacemaker::SlotPacemaker;
+use slot_race::SlotRaceDetector;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_program::{hash::Hash, instruction::Instruction, pubkey::Pubkey};
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    signature::{read_keypair_file, Keypair, Signer},
+};
+use throttle::TransactionThrottle;
+use tip_pool::TipAccountRotator;
+use tpu::TpuSender;
+use utils::SignatureDisplay;
+use wallet_mux::{SelectionStrategy, TransactionSigner, WalletMultiplexer};
+
+// Default JSON RPC address of a local solana-test-validator
+const LOCAL_CLUSTER_URL: &str = "http://127.0.0.1:8899";
+
+struct Miner {
+    pub keypair_filepath: Option<String>,
+    pub priority_fee: u64,
+    pub rpc_client: Arc<RpcClient>,
+    pub rpc_pool: RpcPool,
+    pub send_max_retries_per_rpc: Option<usize>,
+    pub simulate_program_overrides: Vec<ProgramOverride>,
+    pub throttle: Option<Arc<TransactionThrottle>>,
+    pub metrics: Metrics,
+    pub checkpointer: Option<Checkpointer>,
+    pub simulate_fail_fast: bool,
+    pub circuit_breaker: Option<RpcCircuitBreaker>,
+    pub gas_auction_tiers: Vec<u64>,
+    pub claim_on_confirm: bool,
+    pub signature_display: SignatureDisplay,
+    pub max_queue_depth: Option<usize>,
+    pub confirmation_times: ConfirmationTimeTracker,
+    pub account_cache: AccountCache,
+    pub simulate_log_program_addresses: bool,
+    pub fallback_ixs: Option<Vec<Instruction>>,
+    pub print_blockhash: bool,
+    pub fee_history: PriorityFeeHistory,
+    pub fee_tier_model_info: bool,
+    pub confirm_timeout: Option<Duration>,
+    pub log_tx_annotations: bool,
+    pub rpc_ws_url: String,
+    pub confirmation_strategy: ConfirmationStrategy,
+    pub tx_account_count_limit: usize,
+    pub lookup_tables: Vec<Pubkey>,
+    pub profitability_filter: Option<ProfitabilityFilter>,
+    pub simulate_max_retries: Option<usize>,
+    pub pending_signatures: Option<PendingSignatureLimit>,
+    pub blockhash_min_slot_lag: u64,
+    pub slot_race_detector: Option<Arc<SlotRaceDetector>>,
+    pub custom_memo: Option<Vec<u8>>,
+    pub no_auto_batch: bool,
+    pub cu_tuner: CuLimitAutoTuner,
+    pub tpu_sender: Option<TpuSender>,
+    pub simulate_commitment: Option<CommitmentConfig>,
+    pub simulate_log_file: Option<PathBuf>,
+    pub balance_alert: Option<BalanceAlert>,
+    pub slot_pacemaker: Option<Arc<SlotPacemaker>>,
+    pub instruction_dedup: bool,
+    pub track_fee_refunds: bool,
+    pub send_encoding: SendEncoding,
+    pub cost_model: TransactionCostModel,
+    pub wait_finalized: bool,
+    pub simulate_timeout: Duration,
+    pub instruction_encoding: InstructionEncoding,
+    pub simulate_balance_overrides: Vec<BalanceOverride>,
+    pub batch_retry_budget: Option<usize>,
+    pub simulate_with_slots: Option<u64>,
+    pub rpc_selector: Option<Arc<MultiRegionRpcSelector>>,
+    pub mempool_monitor: Option<Arc<TransactionMempoolMonitor>>,
+    pub hash_rate_reporters: Vec<HashRateReporter>,
+    pub simulate_account_owner_check: bool,
+    pub hash_function: HashAlgorithm,
+    pub simulate_log_accounts_diff: bool,
+    pub wallet_multiplexer: Option<WalletMultiplexer>,
+    pub rebroadcaster: Option<Arc<TransactionRebroadcaster>>,
+    pub stagger_batch_sends: Option<Duration>,
+    pub error_decoder: Option<ProgramErrorDecoder>,
+    pub fee_token: Option<TokenAccountMiner>,
+    pub instruction_validator: Option<InstructionValidator>,
+    pub tip_rotator: Option<TipAccountRotator>,
+    pub simulate_min_accounts_for_rent: bool,
+    pub batch_split_strategy: BatchSplitStrategy,
+    pub simulate_disable_caching: bool,
+    pub fee_decay: Option<PriorityFeeDecayScheduler>,
+    pub key_rotator: Option<KeyRotator>,
+    pub difficulty_predictor: Mutex<DifficultyPredictor>,
+    pub nonce_range: Mutex<Option<NonceRange>>,
+    pub blockhash_cache: BlockhashCache,
+}
+
+#[derive(Parser, Debug)]
+#[command(about, version)]
+struct Args {
+    #[arg(
+        long,
+        value_name = "NETWORK_URL",
+        help = "Network address of your RPC provider",
+        global = true
+    )]
+    rpc: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "WEBSOCKET_URL",
+        help = "Websocket address of your RPC provider, derived from --rpc by default",
+        global = true
+    )]
+    rpc_ws_url: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "NETWORK_URL",
+        help = "Additional RPC endpoints to rotate through when sending transactions",
+        value_delimiter = ',',
+        global = true
+    )]
+    rpc_pool: Vec<String>,
+
+    #[arg(
+        long,
+        help = "Use defaults suited to a local solana-test-validator",
+        global = true
+    )]
+    local_cluster: bool,
+
+    #[clap(
+        global = true,
+        short = 'C',
+        long = "config",
+        id = "PATH",
+        help = "Filepath to config file."
+    )]
+    pub config_file: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "KEYPAIR_FILEPATH",
+        help = "Filepath to keypair to use",
+        global = true
+    )]
+    keypair: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "MICROLAMPORTS",
+        help = "Number of microlamports to pay as priority fee per transaction",
+        default_value = "0",
+        global = true
+    )]
+    priority_fee: u64,
+
+    #[arg(
+        long,
+        value_name = "FACTOR",
+        help = "Multiply the priority fee by FACTOR before applying --max-priority-fee",
+        default_value = "1.0",
+        global = true
+    )]
+    compute_unit_price_multiplier: f64,
+
+    #[arg(
+        long,
+        value_name = "MICROLAMPORTS",
+        help = "Maximum priority fee to pay per transaction",
+        global = true
+    )]
+    max_priority_fee: Option<u64>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Number of send attempts per RPC endpoint before rotating to the next",
+        global = true
+    )]
+    send_max_retries_per_rpc: Option<usize>,
+
+    #[arg(
+        long,
+        value_name = "PUBKEY=PATH",
+        help = "Simulate against the program binary at PATH in place of the deployed program",
+        value_parser = parse_program_override,
+        global = true
+    )]
+    simulate_program_override: Vec<ProgramOverride>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Maximum number of transactions to submit per slot",
+        global = true
+    )]
+    max_txs_per_slot: Option<usize>,
+
+    #[arg(
+        long,
+        value_name = "TXNS",
+        help = "Save a checkpoint of miner state every TXNS confirmed transactions",
+        global = true
+    )]
+    checkpoint_interval: Option<u64>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Filepath to save and restore the miner checkpoint",
+        default_value = "ore-checkpoint.json",
+        global = true
+    )]
+    checkpoint_path: PathBuf,
+
+    #[arg(
+        long,
+        help = "Abort the batch on the first simulation error or timeout instead of retrying",
+        global = true
+    )]
+    simulate_fail_fast: bool,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Stop sending RPC requests after N consecutive failures",
+        global = true
+    )]
+    circuit_breaker_threshold: Option<usize>,
+
+    #[arg(
+        long,
+        value_name = "SECS",
+        help = "Seconds to wait before probing an RPC endpoint after the circuit breaker opens",
+        default_value = "30",
+        global = true
+    )]
+    circuit_breaker_cooldown: u64,
+
+    #[arg(
+        long,
+        value_name = "MICROLAMPORTS",
+        help = "Submit each mining transaction once per priority fee tier and keep the first to land",
+        value_delimiter = ',',
+        global = true
+    )]
+    gas_auction_tiers: Vec<u64>,
+
+    #[arg(
+        long,
+        help = "Claim rewards in the same batch as each mining submission",
+        global = true
+    )]
+    claim_on_confirm: bool,
+
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        help = "Format used to print transaction signatures",
+        value_enum,
+        default_value_t = SignatureDisplay::Base58,
+        global = true
+    )]
+    tx_signature_display: SignatureDisplay,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Reject batches with more than N transactions",
+        global = true
+    )]
+    max_queue_depth: Option<usize>,
+
+    #[arg(
+        long,
+        value_name = "MILLISECONDS",
+        help = "Warn when the p99 confirmation latency exceeds this many milliseconds",
+        global = true
+    )]
+    max_confirm_latency_ms: Option<u64>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "File to write the miner_confirm_latency_ms Prometheus gauge to after each confirmation",
+        global = true
+    )]
+    confirm_latency_metrics_file: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "Print the program derived addresses logged during simulation",
+        global = true
+    )]
+    simulate_log_program_addresses: bool,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "JSON file of instructions to send instead when a transaction fails simulation",
+        global = true
+    )]
+    fallback_ixs_file: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "Print the recent blockhash used to sign each transaction",
+        global = true
+    )]
+    print_blockhash: bool,
+
+    #[arg(
+        long,
+        help = "Print the fitted priority fee model and suggested fee tiers while mining",
+        global = true
+    )]
+    fee_tier_model_info: bool,
+
+    #[arg(
+        long,
+        value_name = "SECS",
+        help = "Stop waiting for a transaction to confirm after SECS seconds",
+        global = true
+    )]
+    confirm_timeout: Option<u64>,
+
+    #[arg(
+        long,
+        help = "Print the compute units, fees, and confirmation details of each transaction",
+        global = true
+    )]
+    log_tx_annotations: bool,
+
+    #[arg(
+        long,
+        value_name = "STRATEGY",
+        help = "How to wait for transactions to confirm",
+        value_enum,
+        default_value_t = ConfirmationStrategy::Polling,
+        global = true
+    )]
+    confirmation_strategy: ConfirmationStrategy,
+
+    #[arg(
+        long,
+        help = "Race confirmation polling against a websocket subscription, same as --confirmation-strategy hybrid",
+        conflicts_with = "confirmation_strategy",
+        global = true
+    )]
+    confirm_via_poll_and_ws: bool,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Reject transactions that reference more than N unique accounts before sending",
+        default_value_t = MAX_TX_ACCOUNTS,
+        global = true
+    )]
+    tx_account_count_limit: usize,
+
+    #[arg(
+        long,
+        value_name = "ADDRESS",
+        help = "Address lookup tables to send versioned transactions with",
+        value_delimiter = ',',
+        global = true
+    )]
+    lookup_table: Vec<Pubkey>,
+
+    #[arg(
+        long,
+        help = "Skip submissions whose fee is predicted to exceed the reward",
+        requires = "ore_price",
+        global = true
+    )]
+    require_profitable: bool,
+
+    #[arg(
+        long,
+        value_name = "LAMPORTS",
+        help = "Value of one ORE in lamports, used by --require-profitable",
+        global = true
+    )]
+    ore_price: Option<f64>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Number of times to retry a failed simulation, independent of send retries",
+        global = true
+    )]
+    simulate_max_retries: Option<usize>,
+
+    #[arg(
+        long,
+        help = "Restart the miner with the same arguments if it panics or is killed by a signal, backing off while it keeps crashing",
+        global = true
+    )]
+    auto_restart: bool,
+
+    #[arg(
+        long,
+        value_name = "SECS",
+        help = "Seconds to wait before restarting the miner with --auto-restart",
+        default_value = "5",
+        global = true
+    )]
+    auto_restart_delay: u64,
+
+    #[arg(long, hide = true, global = true)]
+    supervisor_mode: bool,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Pause sending while N transactions are waiting to confirm",
+        global = true
+    )]
+    max_pending_signatures: Option<usize>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Retry fetching the blockhash until it is at least N slots behind the live slot",
+        default_value = "0",
+        global = true
+    )]
+    blockhash_min_slot_lag: u64,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Lock file shared by miner instances, used to warn when two run with one keypair",
+        global = true
+    )]
+    instance_lock_path: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "MEMO",
+        help = "Memo to attach to mining transactions",
+        global = true
+    )]
+    custom_memo: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "ENCODING",
+        help = "Encoding of the --custom-memo value",
+        value_enum,
+        default_value_t = MemoEncoding::Utf8,
+        global = true
+    )]
+    tx_memo_encoding: MemoEncoding,
+
+    #[arg(
+        long,
+        help = "Fail instead of splitting instructions that do not fit in one transaction",
+        global = true
+    )]
+    no_auto_batch: bool,
+
+    #[arg(
+        long,
+        value_name = "FACTOR",
+        help = "Multiply simulated compute units by FACTOR when setting the limit, raised automatically on overruns",
+        default_value = "1.0",
+        global = true
+    )]
+    cu_overhead_factor: f64,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Ignore the first N confirmed transactions when tuning the compute unit overhead factor",
+        default_value = "0",
+        global = true
+    )]
+    cu_sample_warmup: usize,
+
+    #[arg(
+        long,
+        help = "Send transactions to validator TPU ports over QUIC instead of through RPC",
+        global = true
+    )]
+    tpu_quic: bool,
+
+    #[arg(
+        long,
+        value_name = "HOST:PORT",
+        help = "QUIC TPU address of the validator to send to with --tpu-quic, instead of the upcoming leaders",
+        requires = "tpu_quic",
+        global = true
+    )]
+    validator_tpu_host: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "COMMITMENT",
+        help = "Commitment to simulate against, instead of the RPC client's commitment. \
+                processed sees the freshest state but may simulate against a fork that is \
+                dropped; finalized is safest but lags the live slot the most",
+        value_enum,
+        global = true
+    )]
+    simulate_commitment: Option<SimulateCommitment>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Append each simulation's logs to PATH as one JSON object per line",
+        global = true
+    )]
+    simulate_log_to_file: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "LAMPORTS",
+        help = "Alert the --balance-alert-webhook when the signer's balance drops below LAMPORTS",
+        requires = "balance_alert_webhook",
+        global = true
+    )]
+    balance_alert: Option<u64>,
+
+    #[arg(
+        long,
+        value_name = "URL",
+        help = "Webhook to post low balance alerts to as JSON",
+        requires = "balance_alert",
+        global = true
+    )]
+    balance_alert_webhook: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "SECS",
+        help = "Seconds between repeated alerts while the balance stays low",
+        default_value = "300",
+        global = true
+    )]
+    balance_alert_interval_secs: u64,
+
+    #[arg(
+        long,
+        value_name = "MS",
+        help = "Hold each send until MS milliseconds before the predicted start of the next slot",
+        global = true
+    )]
+    tpu_submit_offset_ms: Option<u64>,
+
+    #[arg(
+        long,
+        help = "Drop instructions repeated with the same program, accounts, and data before signing",
+        global = true
+    )]
+    instruction_dedup: bool,
+
+    #[arg(
+        long,
+        help = "Compare the fee charged on each confirmed transaction with the fee predicted and report refunds",
+        global = true
+    )]
+    track_fee_refunds: bool,
+
+    #[arg(
+        long,
+        value_name = "ENCODING",
+        help = "Encoding of transactions sent over RPC. base58 is slower to encode, so only use it for RPC nodes without base64 support",
+        value_enum,
+        default_value_t = SendEncoding::Base64,
+        global = true
+    )]
+    send_encoding: SendEncoding,
+
+    #[arg(
+        long,
+        value_name = "LAMPORTS",
+        help = "Lamports charged per byte of write-locked account data when predicting transaction costs",
+        default_value = "0",
+        global = true
+    )]
+    write_lock_fee_lamports_per_byte: u64,
+
+    #[arg(
+        long,
+        value_name = "LAMPORTS",
+        help = "Lamports charged per byte of account data growth when predicting transaction costs",
+        default_value = "0",
+        global = true
+    )]
+    storage_fee_lamports_per_byte: u64,
+
+    #[arg(
+        long,
+        help = "Wait for every confirmed transaction to reach finalized commitment before moving on",
+        global = true
+    )]
+    wait_finalized: bool,
+
+    #[arg(
+        long,
+        value_name = "SECS",
+        help = "Seconds to wait for a simulation before retrying, falling back to static compute unit limits once retries run out",
+        default_value = "10",
+        global = true
+    )]
+    simulate_timeout: u64,
+
+    #[arg(
+        long,
+        value_name = "ENCODING",
+        help = "Encoding of mine instruction data, for program versions that expect a different layout",
+        value_enum,
+        default_value_t = InstructionEncoding::Raw,
+        global = true
+    )]
+    instruction_encoding: InstructionEncoding,
+
+    #[arg(
+        long,
+        value_name = "PUBKEY=LAMPORTS",
+        help = "Simulate as if the account at PUBKEY held LAMPORTS, to test low balance handling",
+        value_parser = parse_balance_override,
+        global = true
+    )]
+    simulate_override_balance: Vec<BalanceOverride>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Fail a batch once its transactions have retried sends N times in total",
+        global = true
+    )]
+    batch_retry_budget: Option<usize>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Also simulate each transaction in each of the next N slots and warn if the outcome changes",
+        global = true
+    )]
+    simulate_with_slots: Option<u64>,
+
+    #[arg(
+        long,
+        help = "Log each send and confirm step with the transaction index and time since the batch started",
+        global = true
+    )]
+    send_and_confirm_verbose: bool,
+
+    #[arg(
+        long,
+        help = "Send through the RPC pool endpoint with the lowest getSlot latency, re-ranked every 5 minutes",
+        global = true
+    )]
+    rpc_select_by_latency: bool,
 
-use clap::{command, Parser, Subcommand};
-use solana_client::nonblocking::rpc_client::RpcClient;
-use solana_sdk::{
-    commitment_config::CommitmentConfig,
-    signature::{read_keypair_file, Keypair},
-};
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "File to write the rpc_endpoint_latency_ms Prometheus gauge to after each ranking",
+        requires = "rpc_select_by_latency",
+        global = true
+    )]
+    rpc_latency_metrics_file: Option<PathBuf>,
 
-struct Miner {
-    pub keypair_filepath: Option<String>,
-    pub priority_fee: u64,
-    pub rpc_client: Arc<RpcClient>,
-}
+    #[arg(
+        long,
+        help = "Measure how long sent transactions take to reach the RPC node and warn when it is consistently over a second",
+        global = true
+    )]
+    track_mempool_propagation: bool,
 
-#[derive(Parser, Debug)]
-#[command(about, version)]
-struct Args {
     #[arg(
         long,
-        value_name = "NETWORK_URL",
-        help = "Network address of your RPC provider",
+        value_name = "PATH",
+        help = "File to write the miner_mempool_propagation_ms Prometheus histogram to",
+        requires = "track_mempool_propagation",
         global = true
     )]
-    rpc: Option<String>,
+    mempool_metrics_file: Option<PathBuf>,
 
-    #[clap(
-        global = true,
-        short = 'C',
-        long = "config",
-        id = "PATH",
-        help = "Filepath to config file."
+    #[arg(
+        long,
+        help = "Check that Ore accounts are owned by the expected program before simulating",
+        global = true
     )]
-    pub config_file: Option<String>,
+    simulate_account_owner_check: bool,
+
+    #[arg(
+        long,
+        value_name = "ALG",
+        help = "Hash function to search nonces with, for program versions that verify a different one",
+        value_enum,
+        default_value_t = HashAlgorithm::Keccak,
+        global = true
+    )]
+    hash_function: HashAlgorithm,
+
+    #[arg(
+        long,
+        help = "Print the lamport and data changes simulation made to each account in the transaction",
+        global = true
+    )]
+    simulate_log_accounts_diff: bool,
 
     #[arg(
         long,
         value_name = "KEYPAIR_FILEPATH",
-        help = "Filepath to keypair to use",
+        help = "Keypairs to pay for mining transactions in turn, instead of the mining keypair",
+        value_delimiter = ',',
         global = true
     )]
-    keypair: Option<String>,
+    multiplex_keypairs: Vec<String>,
+
+    #[arg(
+        long,
+        value_name = "STRATEGY",
+        help = "How to pick which of --multiplex-keypairs pays for each transaction",
+        value_enum,
+        default_value_t = SelectionStrategy::RoundRobin,
+        global = true
+    )]
+    multiplex_strategy: SelectionStrategy,
+
+    #[arg(
+        long,
+        value_name = "SECS",
+        help = "Re-send transactions that have not landed every SECS seconds until their blockhash expires",
+        global = true
+    )]
+    rebroadcast_interval: Option<u64>,
+
+    #[arg(
+        long,
+        value_name = "MS",
+        help = "Wait MS milliseconds between the sends of a batch, to spread load on the RPC",
+        global = true
+    )]
+    stagger_batch_sends: Option<u64>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "JSON file mapping custom program error codes to names, to name them in errors",
+        global = true
+    )]
+    error_table: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "PUBKEY",
+        help = "Experimental: mint of the SPL token to pay priority fees in through --fee-token-program, whose interface is assumed",
+        requires_all = ["fee_token_account", "fee_token_program", "fee_token_price"],
+        global = true
+    )]
+    fee_token_mint: Option<Pubkey>,
+
+    #[arg(
+        long,
+        value_name = "PUBKEY",
+        help = "Token account to pay priority fees from",
+        requires = "fee_token_mint",
+        global = true
+    )]
+    fee_token_account: Option<Pubkey>,
+
+    #[arg(
+        long,
+        value_name = "PUBKEY",
+        help = "Fee-compression program whose pay_fee_with_token instruction is prepended to each transaction",
+        requires = "fee_token_mint",
+        global = true
+    )]
+    fee_token_program: Option<Pubkey>,
+
+    #[arg(
+        long,
+        value_name = "TOKENS_PER_SOL",
+        help = "Fixed price of SOL in fee token base units, to convert priority fees at, since no oracle is consulted",
+        requires = "fee_token_mint",
+        global = true
+    )]
+    fee_token_price: Option<f64>,
+
+    #[arg(
+        long,
+        help = "Check the cluster's feature gates at startup and turn off options it does not support",
+        global = true
+    )]
+    check_feature_gates: bool,
+
+    #[arg(
+        long,
+        value_name = "TYPE",
+        help = "How the Ore program identifies instructions, to validate instructions against before sending",
+        value_enum,
+        global = true
+    )]
+    program_type: Option<ProgramType>,
+
+    #[arg(
+        long,
+        help = "Validate instruction accounts but not discriminators, for programs with a different instruction layout",
+        requires = "program_type",
+        global = true
+    )]
+    skip_anchor_discriminator_check: bool,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Number of durable nonce tip accounts created by setup-tip-pool to rotate through",
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..),
+        global = true
+    )]
+    tip_pool_size: Option<usize>,
+
+    #[arg(
+        long,
+        help = "Check that writable accounts hold the rent-exempt minimum for their size before simulating",
+        global = true
+    )]
+    simulate_min_accounts_for_rent: bool,
+
+    #[arg(
+        long,
+        value_name = "STRATEGY",
+        help = "How to split instructions between transactions: greedy-fit, max-parallelism, or equal-split=N",
+        value_parser = parse_batch_split_strategy,
+        default_value = "greedy-fit",
+        global = true
+    )]
+    batch_split_strategy: BatchSplitStrategy,
+
+    #[arg(
+        long,
+        help = "Bypass the account and blockhash caches, fetching them fresh for every transaction and simulation",
+        global = true
+    )]
+    simulate_disable_caching: bool,
 
     #[arg(
         long,
         value_name = "MICROLAMPORTS",
-        help = "Number of microlamports to pay as priority fee per transaction",
-        default_value = "0",
+        help = "Raise the priority fee by MICROLAMPORTS on every send retry, up to --max-priority-fee, and lower it by as much after every confirmed transaction, down to --priority-fee. Retries resend the same signed transaction, so a raised fee applies from the next transaction built.",
         global = true
     )]
-    priority_fee: u64,
+    fee_decay_step: Option<u64>,
+
+    #[arg(
+        long,
+        value_name = "TXNS",
+        help = "Pay fees from a keypair that is replaced, and its balance moved to the new one, after every TXNS confirmed transactions. The mining keypair does not change.",
+        conflicts_with = "multiplex_keypairs",
+        global = true
+    )]
+    rotate_keypair_every: Option<u64>,
+
+    #[arg(
+        long,
+        value_name = "SECS",
+        help = "Replace the --rotate-keypair-every fee payer after SECS seconds too, or on its own",
+        conflicts_with = "multiplex_keypairs",
+        global = true
+    )]
+    rotate_keypair_interval: Option<u64>,
+
+    #[arg(
+        long,
+        value_name = "DIR",
+        help = "Directory the rotating fee payer keypairs are saved in. Fund fee-payer.json there before the first rotation.",
+        default_value = "ore-fee-payers",
+        global = true
+    )]
+    rotate_keypair_dir: PathBuf,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Hash-chained log that every fee payer rotation is appended to",
+        default_value = "ore-rotation-log.jsonl",
+        global = true
+    )]
+    rotation_log: PathBuf,
+
+    #[cfg(feature = "verbose-rpc")]
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Write every RPC response to a gzip-compressed JSONL file at PATH",
+        global = true
+    )]
+    log_rpc_responses: Option<PathBuf>,
+
+    #[cfg(feature = "verbose-rpc")]
+    #[arg(
+        long,
+        value_name = "MB",
+        help = "Size at which the --log-rpc-responses file is rotated to PATH.1",
+        default_value = "100",
+        global = true
+    )]
+    log_rpc_max_mb: u64,
 
     #[command(subcommand)]
     command: Commands,
@@ -79,15 +1039,41 @@ enum Commands {
     #[command(about = "Fetch the distributable rewards of the busses")]
     Busses(BussesArgs),
 
+    #[command(about = "Poll the Jito block engine until a bundle lands")]
+    BundleStatus(BundleStatusArgs),
+
+    #[command(about = "Estimate the fees of claiming your mining rewards")]
+    EstimateFee(EstimateFeeArgs),
+
     #[command(about = "Mine Ore using local compute")]
     Mine(MineArgs),
 
     #[command(about = "Claim available mining rewards")]
     Claim(ClaimArgs),
 
+    #[command(
+        about = "Claim the rewards of several miners together, paying fees from this keypair"
+    )]
+    ClaimAll(ClaimAllArgs),
+
+    #[command(about = "List upcoming leaders and the TPU addresses they advertise")]
+    Leaders(LeadersArgs),
+
+    #[command(about = "Stream the logs of transactions that mention the Ore program")]
+    Logs(LogsArgs),
+
+    #[command(about = "Estimate the hash rate of miners with recent successful submissions")]
+    Peers(PeersArgs),
+
     #[command(about = "Fetch your balance of unclaimed mining rewards")]
     Rewards(RewardsArgs),
 
+    #[command(about = "Check that no entry of a keypair rotation log was altered")]
+    VerifyRotationLog(VerifyRotationLogArgs),
+
+    #[command(about = "Create and fund durable nonce tip accounts for Jito bundles")]
+    SetupTipPool(SetupTipPoolArgs),
+
     #[command(about = "Fetch the treasury account and balance")]
     Treasury(TreasuryArgs),
 
@@ -117,6 +1103,67 @@ struct BalanceArgs {
 #[derive(Parser, Debug)]
 struct BussesArgs {}
 
+#[derive(Parser, Debug)]
+struct BundleStatusArgs {
+    #[arg(
+        value_name = "BUNDLE_ID",
+        help = "The id returned when the bundle was sent"
+    )]
+    pub bundle_id: String,
+
+    #[arg(
+        long,
+        value_name = "NETWORK_URL",
+        help = "Network address of the Jito block engine",
+        default_value = "https://mainnet.block-engine.jito.wtf"
+    )]
+    pub block_engine_url: String,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Number of times to poll before giving up",
+        default_value = "30"
+    )]
+    pub max_attempts: usize,
+
+    #[arg(
+        long,
+        value_name = "INDEX",
+        help = "Tip pool account the bundle used, whose nonce is advanced once it lands",
+        requires = "tip_pool_size"
+    )]
+    pub tip_account_index: Option<usize>,
+
+    #[arg(
+        long,
+        value_name = "HASH",
+        help = "Durable nonce the bundle was built on. If set, gives up once the tip account's nonce changes without the bundle landing.",
+        requires = "tip_account_index"
+    )]
+    pub expected_nonce: Option<Hash>,
+}
+
+#[derive(Parser, Debug)]
+struct EstimateFeeArgs {}
+
+#[derive(Parser, Debug)]
+struct LeadersArgs {}
+
+#[derive(Parser, Debug)]
+struct LogsArgs {}
+
+#[derive(Parser, Debug)]
+struct PeersArgs {
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Number of recent Ore program transactions to inspect",
+        default_value = "100"
+    )]
+    pub limit: usize,
+}
+
 #[derive(Parser, Debug)]
 struct RewardsArgs {
     #[arg(
@@ -142,6 +1189,16 @@ struct MineArgs {
 #[derive(Parser, Debug)]
 struct TreasuryArgs {}
 
+#[derive(Parser, Debug)]
+struct SetupTipPoolArgs {
+    #[arg(
+        value_name = "N",
+        help = "The number of tip accounts to create",
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    pub size: usize,
+}
+
 #[derive(Parser, Debug)]
 struct ClaimArgs {
     #[arg(
@@ -159,6 +1216,24 @@ struct ClaimArgs {
     beneficiary: Option<String>,
 }
 
+#[derive(Parser, Debug)]
+struct VerifyRotationLogArgs {
+    #[arg(value_name = "PATH", help = "The rotation log to verify")]
+    path: PathBuf,
+}
+
+#[derive(Parser, Debug)]
+struct ClaimAllArgs {
+    #[arg(
+        long,
+        value_name = "PATHS",
+        help = "Comma-separated keypair files of the miners to claim for",
+        value_delimiter = ',',
+        required = true
+    )]
+    keypairs: Vec<String>,
+}
+
 #[cfg(feature = "admin")]
 #[derive(Parser, Debug)]
 struct InitializeArgs {}
@@ -176,6 +1251,23 @@ struct UpdateDifficultyArgs {}
 #[tokio::main]
 async fn main() {
     let args = Args::parse();
+    if args.send_and_confirm_verbose {
+        env_logger::Builder::new()
+            .parse_filters(&format!("{}=debug", module_path!()))
+            .init();
+    }
+
+    // Supervise a child miner instead of mining. The child runs without these flags.
+    if args.auto_restart || args.supervisor_mode {
+        let restarter = AutoRestarter::new(Duration::from_secs(args.auto_restart_delay));
+        match restarter.run() {
+            Ok(status) => std::process::exit(status.code().unwrap_or_default()),
+            Err(err) => {
+                eprintln!("error: Failed to start miner: {}", err);
+                std::process::exit(1);
+            }
+        }
+    }
 
     // Load the config file from custom path, the default path, or use default config values
     let cli_config = if let Some(config_file) = &args.config_file {
@@ -190,15 +1282,303 @@ async fn main() {
     };
 
     // Initialize miner.
-    let cluster = args.rpc.unwrap_or(cli_config.json_rpc_url);
+    let (cluster, commitment) = if args.local_cluster {
+        (
+            args.rpc.unwrap_or(LOCAL_CLUSTER_URL.to_string()),
+            CommitmentConfig::confirmed(),
+        )
+    } else {
+        (
+            args.rpc.unwrap_or(cli_config.json_rpc_url),
+            CommitmentConfig::finalized(),
+        )
+    };
     let default_keypair = args.keypair.unwrap_or(cli_config.keypair_path);
-    let rpc_client = RpcClient::new_with_commitment(cluster, CommitmentConfig::finalized());
+    #[cfg(feature = "verbose-rpc")]
+    let rpc_client = match args.log_rpc_responses {
+        Some(path) => rpc_log::logging_rpc_client(cluster, commitment, path, args.log_rpc_max_mb)
+            .unwrap_or_else(|err| {
+                eprintln!("error: Failed to open RPC response log: {}", err);
+                std::process::exit(1);
+            }),
+        None => RpcClient::new_with_commitment(cluster, commitment),
+    };
+    #[cfg(not(feature = "verbose-rpc"))]
+    let rpc_client = RpcClient::new_with_commitment(cluster, commitment);
+    let rpc_client = Arc::new(rpc_client);
+
+    let fallback_ixs = args.fallback_ixs_file.map(|path| {
+        load_instructions(&path).unwrap_or_else(|err| {
+            eprintln!("error: {}", err);
+            std::process::exit(1);
+        })
+    });
+
+    let error_decoder = args.error_table.map(|path| {
+        ProgramErrorDecoder::load(&path).unwrap_or_else(|err| {
+            eprintln!("error: {}", err);
+            std::process::exit(1);
+        })
+    });
+
+    let custom_memo = args.custom_memo.map(|memo| {
+        args.tx_memo_encoding.decode(&memo).unwrap_or_else(|err| {
+            eprintln!("error: {}", err);
+            std::process::exit(1);
+        })
+    });
+
+    let rpc_ws_url = args
+        .rpc_ws_url
+        .unwrap_or_else(|| solana_cli_config::Config::compute_websocket_url(&rpc_client.url()));
 
-    let miner = Arc::new(Miner::new(
-        Arc::new(rpc_client),
+    if matches!(args.send_encoding, SendEncoding::Base58) {
+        println!(
+            "Warning: base58 is significantly slower to encode than base64 for large transactions"
+        );
+    }
+
+    let mut priority_fee = scale_priority_fee(
         args.priority_fee,
-        Some(default_keypair),
-    ));
+        args.compute_unit_price_multiplier,
+        args.max_priority_fee,
+    );
+    let mut lookup_tables = args.lookup_table;
+    let mut fee_decay_step = args.fee_decay_step;
+    let mut tpu_quic = args.tpu_quic;
+    if args.check_feature_gates {
+        let checker = SolanaFeatureGateChecker::new(rpc_client.clone());
+        match ClusterFeatures::detect(&checker).await {
+            Ok(features) => {
+                if !features.versioned_transactions && !lookup_tables.is_empty() {
+                    println!(
+                        "Cluster does not support versioned transactions, ignoring --lookup-table"
+                    );
+                    lookup_tables.clear();
+                }
+                if !features.compute_unit_price && priority_fee > 0 {
+                    println!("Cluster does not support priority fees, sending without them");
+                    priority_fee = 0;
+                    fee_decay_step = None;
+                }
+                if !features.tpu_quic && tpu_quic {
+                    println!("Cluster does not accept transactions over QUIC, ignoring --tpu-quic");
+                    tpu_quic = false;
+                }
+            }
+            Err(err) => println!("Failed to check feature gates: {}", err),
+        }
+    }
+
+    let tpu_sender = if tpu_quic {
+        let validator = args.validator_tpu_host.map(|host| {
+            host.to_socket_addrs()
+                .ok()
+                .and_then(|mut addrs| addrs.next())
+                .unwrap_or_else(|| {
+                    eprintln!("error: Could not resolve `{}`", host);
+                    std::process::exit(1);
+                })
+        });
+        match TpuSender::new(rpc_client.clone(), validator).await {
+            Ok(tpu_sender) => Some(tpu_sender),
+            Err(err) => {
+                eprintln!("error: Failed to connect to the TPU: {}", err);
+                std::process::exit(1);
+            }
+        }
+    } else {
+        None
+    };
+
+    let wallet_multiplexer = (!args.multiplex_keypairs.is_empty()).then(|| {
+        let keypairs = args
+            .multiplex_keypairs
+            .iter()
+            .map(|path| {
+                let keypair = read_keypair_file(path).unwrap_or_else(|err| {
+                    eprintln!("error: Failed to read keypair {}: {}", path, err);
+                    std::process::exit(1);
+                });
+                Arc::new(keypair) as Arc<dyn TransactionSigner>
+            })
+            .collect();
+        WalletMultiplexer::new(keypairs, args.multiplex_strategy)
+    });
+
+    let key_rotator = (args.rotate_keypair_every.is_some()
+        || args.rotate_keypair_interval.is_some())
+    .then(|| {
+        KeyRotator::open(
+            args.rotate_keypair_dir,
+            KeyRotationLog::new(args.rotation_log),
+            args.rotate_keypair_every,
+            args.rotate_keypair_interval.map(Duration::from_secs),
+        )
+        .unwrap_or_else(|err| {
+            eprintln!("error: Failed to load the rotating fee payer: {}", err);
+            std::process::exit(1);
+        })
+    });
+    if let Some(rotator) = key_rotator.as_ref() {
+        println!("Paying fees from {}", rotator.current().pubkey());
+    }
+
+    let rpc_pool = RpcPool::new(rpc_client.clone(), args.rpc_pool);
+    let rpc_selector = args.rpc_select_by_latency.then(|| {
+        Arc::new(MultiRegionRpcSelector::new(
+            &rpc_pool,
+            args.rpc_latency_metrics_file,
+        ))
+    });
+    let miner = Arc::new(Miner {
+        rpc_pool,
+        send_max_retries_per_rpc: args.send_max_retries_per_rpc,
+        simulate_program_overrides: args.simulate_program_override,
+        throttle: args
+            .max_txs_per_slot
+            .map(|max_per_slot| Arc::new(TransactionThrottle::new(max_per_slot))),
+        checkpointer: args.checkpoint_interval.map(|interval| Checkpointer {
+            interval,
+            path: args.checkpoint_path,
+        }),
+        simulate_fail_fast: args.simulate_fail_fast,
+        circuit_breaker: args.circuit_breaker_threshold.map(|threshold| {
+            RpcCircuitBreaker::new(
+                threshold,
+                Duration::from_secs(args.circuit_breaker_cooldown),
+            )
+        }),
+        gas_auction_tiers: args.gas_auction_tiers,
+        claim_on_confirm: args.claim_on_confirm,
+        signature_display: args.tx_signature_display,
+        max_queue_depth: args.max_queue_depth,
+        confirmation_times: ConfirmationTimeTracker::new(
+            args.max_confirm_latency_ms,
+            args.confirm_latency_metrics_file,
+        ),
+        simulate_log_program_addresses: args.simulate_log_program_addresses,
+        fallback_ixs,
+        print_blockhash: args.print_blockhash,
+        fee_tier_model_info: args.fee_tier_model_info,
+        confirm_timeout: args.confirm_timeout.map(Duration::from_secs),
+        log_tx_annotations: args.log_tx_annotations,
+        confirmation_strategy: if args.confirm_via_poll_and_ws {
+            ConfirmationStrategy::Hybrid
+        } else {
+            args.confirmation_strategy
+        },
+        tx_account_count_limit: args.tx_account_count_limit,
+        lookup_tables,
+        profitability_filter: args
+            .ore_price
+            .filter(|_| args.require_profitable)
+            .map(ProfitabilityFilter::new),
+        simulate_max_retries: args.simulate_max_retries,
+        pending_signatures: args.max_pending_signatures.map(PendingSignatureLimit::new),
+        blockhash_min_slot_lag: args.blockhash_min_slot_lag,
+        slot_race_detector: args
+            .instance_lock_path
+            .map(|path| Arc::new(SlotRaceDetector::new(path))),
+        custom_memo,
+        no_auto_batch: args.no_auto_batch,
+        cu_tuner: CuLimitAutoTuner::new(args.cu_overhead_factor, args.cu_sample_warmup),
+        tpu_sender,
+        simulate_commitment: args.simulate_commitment.map(SimulateCommitment::config),
+        simulate_log_file: args.simulate_log_to_file,
+        balance_alert: args.balance_alert.zip(args.balance_alert_webhook).map(
+            |(threshold, webhook_url)| {
+                BalanceAlert::new(
+                    threshold,
+                    Duration::from_secs(args.balance_alert_interval_secs),
+                    webhook_url,
+                )
+            },
+        ),
+        slot_pacemaker: args
+            .tpu_submit_offset_ms
+            .map(|offset| Arc::new(SlotPacemaker::new(Duration::from_millis(offset)))),
+        instruction_dedup: args.instruction_dedup,
+        track_fee_refunds: args.track_fee_refunds,
+        send_encoding: args.send_encoding,
+        cost_model: TransactionCostModel::new(
+            args.write_lock_fee_lamports_per_byte,
+            args.storage_fee_lamports_per_byte,
+        ),
+        wait_finalized: args.wait_finalized,
+        simulate_timeout: Duration::from_secs(args.simulate_timeout),
+        instruction_encoding: args.instruction_encoding,
+        simulate_balance_overrides: args.simulate_override_balance,
+        batch_retry_budget: args.batch_retry_budget,
+        simulate_with_slots: args.simulate_with_slots,
+        rpc_selector,
+        mempool_monitor: args
+            .track_mempool_propagation
+            .then(|| Arc::new(TransactionMempoolMonitor::new(args.mempool_metrics_file))),
+        hash_rate_reporters: match &args.command {
+            Commands::Mine(args) => HashRateReporter::for_threads(args.threads as usize),
+            _ => vec![],
+        },
+        simulate_account_owner_check: args.simulate_account_owner_check,
+        hash_function: args.hash_function,
+        simulate_log_accounts_diff: args.simulate_log_accounts_diff,
+        wallet_multiplexer,
+        rebroadcaster: args
+            .rebroadcast_interval
+            .map(|secs| Arc::new(TransactionRebroadcaster::new(Duration::from_secs(secs)))),
+        stagger_batch_sends: args.stagger_batch_sends.map(Duration::from_millis),
+        error_decoder,
+        fee_token: args.fee_token_mint.map(|mint| TokenAccountMiner {
+            program_id: args
+                .fee_token_program
+                .expect("required by --fee-token-mint"),
+            mint,
+            token_account: args
+                .fee_token_account
+                .expect("required by --fee-token-mint"),
+            tokens_per_sol: args.fee_token_price.expect("required by --fee-token-mint"),
+        }),
+        instruction_validator: args.program_type.map(|program_type| InstructionValidator {
+            program_type,
+            check_discriminator: !args.skip_anchor_discriminator_check,
+        }),
+        tip_rotator: args.tip_pool_size.map(|size| {
+            let authority = read_keypair_file(&default_keypair).unwrap_or_else(|err| {
+                eprintln!("error: Failed to read keypair {}: {}", default_keypair, err);
+                std::process::exit(1);
+            });
+            TipAccountRotator::new(&authority.pubkey(), size)
+        }),
+        simulate_min_accounts_for_rent: args.simulate_min_accounts_for_rent,
+        batch_split_strategy: args.batch_split_strategy,
+        simulate_disable_caching: args.simulate_disable_caching,
+        fee_decay: fee_decay_step
+            .map(|step| PriorityFeeDecayScheduler::new(priority_fee, step, args.max_priority_fee)),
+        key_rotator,
+        rpc_ws_url,
+        ..Miner::new(rpc_client, priority_fee, Some(default_keypair))
+    });
+    if !miner.simulate_program_overrides.is_empty() || !miner.simulate_balance_overrides.is_empty()
+    {
+        match miner.supports_account_overrides().await {
+            Ok(true) => {}
+            Ok(false) => {
+                eprintln!("error: The RPC node ignores simulation account overrides");
+                std::process::exit(1);
+            }
+            Err(err) => {
+                eprintln!(
+                    "error: Failed to check for simulation override support: {}",
+                    err
+                );
+                std::process::exit(1);
+            }
+        }
+    }
+    miner.restore_checkpoint();
+    miner.spawn_slot_pacemaker();
+    miner.spawn_rebroadcaster();
+    miner.spawn_rpc_selector().await;
 
     // Execute user command.
     match args.command {
@@ -208,9 +1588,38 @@ async fn main() {
         Commands::Busses(_) => {
             miner.busses().await;
         }
+        Commands::BundleStatus(args) => {
+            miner
+                .bundle_status(
+                    &args.block_engine_url,
+                    &args.bundle_id,
+                    args.max_attempts,
+                    args.tip_account_index,
+                    args.expected_nonce,
+                )
+                .await;
+        }
+        Commands::EstimateFee(_) => {
+            miner.estimate_fee().await;
+        }
+        Commands::Leaders(_) => {
+            miner.leaders().await;
+        }
+        Commands::Logs(_) => {
+            miner.logs().await;
+        }
+        Commands::Peers(args) => {
+            miner.peers(args.limit).await;
+        }
         Commands::Rewards(args) => {
             miner.rewards(args.address).await;
         }
+        Commands::VerifyRotationLog(args) => {
+            miner.verify_rotation_log(args.path);
+        }
+        Commands::SetupTipPool(args) => {
+            miner.setup_tip_pool(args.size).await;
+        }
         Commands::Treasury(_) => {
             miner.treasury().await;
         }
@@ -220,6 +1629,9 @@ async fn main() {
         Commands::Claim(args) => {
             miner.claim(args.beneficiary, args.amount).await;
         }
+        Commands::ClaimAll(args) => {
+            miner.claim_all(args.keypairs).await;
+        }
         #[cfg(feature = "admin")]
         Commands::Initialize(_) => {
             miner.initialize().await;
@@ -236,11 +1648,84 @@ async fn main() {
 }
 
 impl Miner {
-    pub fn new(rpc_client: Arc<RpcClient>, priority_fee: u64, keypair_filepath: Option<String>) -> Self {
+    pub fn new(
+        rpc_client: Arc<RpcClient>,
+        priority_fee: u64,
+        keypair_filepath: Option<String>,
+    ) -> Self {
         Self {
+            rpc_pool: RpcPool::new(rpc_client.clone(), vec![]),
+            rpc_ws_url: solana_cli_config::Config::compute_websocket_url(&rpc_client.url()),
             rpc_client,
             keypair_filepath,
             priority_fee,
+            send_max_retries_per_rpc: None,
+            simulate_program_overrides: vec![],
+            throttle: None,
+            metrics: Metrics::default(),
+            checkpointer: None,
+            simulate_fail_fast: false,
+            circuit_breaker: None,
+            gas_auction_tiers: vec![],
+            claim_on_confirm: false,
+            signature_display: SignatureDisplay::default(),
+            max_queue_depth: None,
+            confirmation_times: ConfirmationTimeTracker::new(None, None),
+            account_cache: AccountCache::default(),
+            simulate_log_program_addresses: false,
+            fallback_ixs: None,
+            print_blockhash: false,
+            fee_history: PriorityFeeHistory::default(),
+            fee_tier_model_info: false,
+            confirm_timeout: None,
+            log_tx_annotations: false,
+            confirmation_strategy: ConfirmationStrategy::default(),
+            tx_account_count_limit: MAX_TX_ACCOUNTS,
+            lookup_tables: vec![],
+            profitability_filter: None,
+            simulate_max_retries: None,
+            pending_signatures: None,
+            blockhash_min_slot_lag: 0,
+            slot_race_detector: None,
+            custom_memo: None,
+            no_auto_batch: false,
+            cu_tuner: CuLimitAutoTuner::new(1.0, 0),
+            tpu_sender: None,
+            simulate_commitment: None,
+            simulate_log_file: None,
+            balance_alert: None,
+            slot_pacemaker: None,
+            instruction_dedup: false,
+            track_fee_refunds: false,
+            send_encoding: SendEncoding::default(),
+            cost_model: TransactionCostModel::default(),
+            wait_finalized: false,
+            simulate_timeout: Duration::from_secs(10),
+            instruction_encoding: InstructionEncoding::default(),
+            simulate_balance_overrides: vec![],
+            batch_retry_budget: None,
+            simulate_with_slots: None,
+            rpc_selector: None,
+            mempool_monitor: None,
+            hash_rate_reporters: vec![],
+            simulate_account_owner_check: false,
+            hash_function: HashAlgorithm::default(),
+            simulate_log_accounts_diff: false,
+            wallet_multiplexer: None,
+            rebroadcaster: None,
+            stagger_batch_sends: None,
+            error_decoder: None,
+            fee_token: None,
+            instruction_validator: None,
+            tip_rotator: None,
+            simulate_min_accounts_for_rent: false,
+            batch_split_strategy: BatchSplitStrategy::default(),
+            simulate_disable_caching: false,
+            fee_decay: None,
+            key_rotator: None,
+            difficulty_predictor: Mutex::new(DifficultyPredictor::default()),
+            nonce_range: Mutex::new(None),
+            blockhash_cache: BlockhashCache::default(),
         }
     }
 
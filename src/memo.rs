@@ -0,0 +1,51 @@
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use clap::ValueEnum;
+use solana_program::instruction::Instruction;
+
+use crate::{
+    cu_limits::{CU_LIMIT_MEMO_BASE, CU_LIMIT_MEMO_PER_BYTE},
+    utils::from_hex,
+    Miner,
+};
+
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+pub enum MemoEncoding {
+    #[default]
+    Utf8,
+    Hex,
+    Base64,
+}
+
+impl MemoEncoding {
+    /// Decodes a memo passed on the command line. The memo program rejects memos that are
+    /// not UTF-8, so decoded bytes are checked too.
+    pub fn decode(&self, memo: &str) -> Result<Vec<u8>, String> {
+        let data = match self {
+            MemoEncoding::Utf8 => return Ok(memo.as_bytes().to_vec()),
+            MemoEncoding::Hex => from_hex(memo)?,
+            MemoEncoding::Base64 => BASE64
+                .decode(memo)
+                .map_err(|err| format!("invalid base64 memo: {}", err))?,
+        };
+        std::str::from_utf8(&data)
+            .map_err(|err| format!("memo is not valid UTF-8 once decoded: {}", err))?;
+        Ok(data)
+    }
+}
+
+impl Miner {
+    /// Returns the `--custom-memo` instruction to attach to mining transactions, if any.
+    pub fn memo_ix(&self) -> Option<Instruction> {
+        self.custom_memo
+            .as_ref()
+            .map(|memo| spl_memo::build_memo(memo, &[]))
+    }
+
+    /// Compute units the `memo_ix()` instruction adds to a transaction's limit.
+    pub fn memo_compute_units(&self) -> u32 {
+        self.custom_memo.as_ref().map_or(0, |memo| {
+            CU_LIMIT_MEMO_BASE
+                .saturating_add(CU_LIMIT_MEMO_PER_BYTE.saturating_mul(memo.len() as u32))
+        })
+    }
+}
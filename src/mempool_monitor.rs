@@ -0,0 +1,141 @@
+use std::{
+    collections::VecDeque,
+    fmt::Write as _,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crate::Miner;
+use solana_sdk::signature::Signature;
+
+// Upper bounds of the propagation histogram buckets, in milliseconds
+const BUCKETS_MS: [u64; 7] = [50, 100, 250, 500, 1000, 2000, 5000];
+
+const POLL_DELAY: Duration = Duration::from_millis(50);
+
+// Transactions not seen by then have most likely been dropped
+const POLL_TIMEOUT: Duration = Duration::from_secs(30);
+
+const SLOW_PROPAGATION: Duration = Duration::from_secs(1);
+
+// Consecutive slow transactions before propagation counts as consistently slow
+const SLOW_STREAK: usize = 10;
+
+#[derive(Default)]
+struct Propagation {
+    // Per bucket in `BUCKETS_MS`, then one for slower transactions
+    buckets: [u64; BUCKETS_MS.len() + 1],
+    sum_ms: u64,
+    count: u64,
+    recent: VecDeque<Duration>,
+    alerted: bool,
+}
+
+/// Measures how long submitted transactions take to reach the node, as the time from
+/// send until `getSignatureStatuses` first reports them.
+pub struct TransactionMempoolMonitor {
+    propagation: Mutex<Propagation>,
+    metrics_file: Option<PathBuf>,
+}
+
+impl TransactionMempoolMonitor {
+    pub fn new(metrics_file: Option<PathBuf>) -> Self {
+        Self {
+            propagation: Mutex::new(Propagation::default()),
+            metrics_file,
+        }
+    }
+
+    /// Records one propagation time, warning once propagation has exceeded a second for
+    /// `SLOW_STREAK` transactions in a row.
+    pub fn observe(&self, latency: Duration) {
+        let mut propagation = self.propagation.lock().expect("failed to lock mutex");
+        let latency_ms = latency.as_millis() as u64;
+        let bucket = BUCKETS_MS
+            .iter()
+            .position(|bound| latency_ms <= *bound)
+            .unwrap_or(BUCKETS_MS.len());
+        propagation.buckets[bucket] += 1;
+        propagation.sum_ms += latency_ms;
+        propagation.count += 1;
+        propagation.recent.push_back(latency);
+        if propagation.recent.len() > SLOW_STREAK {
+            propagation.recent.pop_front();
+        }
+
+        let slow = propagation.recent.len() == SLOW_STREAK
+            && propagation
+                .recent
+                .iter()
+                .all(|latency| *latency > SLOW_PROPAGATION);
+        if slow && !propagation.alerted {
+            println!(
+                "Warning: the last {} transactions took over {} ms to reach the RPC node",
+                SLOW_STREAK,
+                SLOW_PROPAGATION.as_millis()
+            );
+        } else if !slow && propagation.alerted {
+            println!("Transaction propagation recovered");
+        }
+        propagation.alerted = slow;
+
+        if let Some(path) = self.metrics_file.as_ref() {
+            if let Err(err) = write_metrics(path, &propagation) {
+                println!("Failed to write propagation metrics to {:?}: {}", path, err);
+            }
+        }
+    }
+}
+
+/// Writes the `miner_mempool_propagation_ms` histogram in the Prometheus text format.
+fn write_metrics(path: &Path, propagation: &Propagation) -> io::Result<()> {
+    let mut text = String::from(
+        "# HELP miner_mempool_propagation_ms Time from send until the RPC node reports the transaction\n\
+         # TYPE miner_mempool_propagation_ms histogram\n",
+    );
+    let mut cumulative = 0;
+    for (bound, count) in BUCKETS_MS.iter().zip(propagation.buckets) {
+        cumulative += count;
+        writeln!(
+            text,
+            "miner_mempool_propagation_ms_bucket{{le=\"{}\"}} {}",
+            bound, cumulative
+        )
+        .ok();
+    }
+    writeln!(
+        text,
+        "miner_mempool_propagation_ms_bucket{{le=\"+Inf\"}} {0}\n\
+         miner_mempool_propagation_ms_sum {1}\n\
+         miner_mempool_propagation_ms_count {0}",
+        propagation.count, propagation.sum_ms
+    )
+    .ok();
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, text)?;
+    fs::rename(tmp_path, path)
+}
+
+impl Miner {
+    /// Polls for `sig` in the background until the node reports it, recording how long
+    /// that took after `sent_at`.
+    pub fn watch_propagation(&self, sig: Signature, sent_at: Instant) {
+        let Some(monitor) = self.mempool_monitor.clone() else {
+            return;
+        };
+        let client = self.rpc_client.clone();
+        tokio::spawn(async move {
+            while sent_at.elapsed() < POLL_TIMEOUT {
+                if let Ok(statuses) = client.get_signature_statuses(&[sig]).await {
+                    if statuses.value.first().is_some_and(Option::is_some) {
+                        monitor.observe(sent_at.elapsed());
+                        return;
+                    }
+                }
+                tokio::time::sleep(POLL_DELAY).await;
+            }
+        });
+    }
+}
@@ -0,0 +1,56 @@
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::hash_rate::HashRateReporter;
+
+#[derive(Default)]
+pub struct Metrics {
+    pub transactions_sent: AtomicU64,
+    pub transactions_confirmed: AtomicU64,
+    pub submissions_skipped: AtomicU64,
+    pub total_fees_refunded: AtomicU64,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct MetricsSnapshot {
+    pub transactions_sent: u64,
+    pub transactions_confirmed: u64,
+    #[serde(default)]
+    pub submissions_skipped: u64,
+    #[serde(default)]
+    pub total_fees_refunded: u64,
+}
+
+impl Metrics {
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        MetricsSnapshot {
+            transactions_sent: self.transactions_sent.load(Ordering::Relaxed),
+            transactions_confirmed: self.transactions_confirmed.load(Ordering::Relaxed),
+            submissions_skipped: self.submissions_skipped.load(Ordering::Relaxed),
+            total_fees_refunded: self.total_fees_refunded.load(Ordering::Relaxed),
+        }
+    }
+
+    pub fn restore(&self, snapshot: &MetricsSnapshot) {
+        self.transactions_sent
+            .store(snapshot.transactions_sent, Ordering::Relaxed);
+        self.transactions_confirmed
+            .store(snapshot.transactions_confirmed, Ordering::Relaxed);
+        self.submissions_skipped
+            .store(snapshot.submissions_skipped, Ordering::Relaxed);
+        self.total_fees_refunded
+            .store(snapshot.total_fees_refunded, Ordering::Relaxed);
+    }
+
+    /// Combined hash rate of all mining threads over the `elapsed` since they were reset.
+    pub fn total_hashes_per_second(reporters: &[HashRateReporter], elapsed: Duration) -> f64 {
+        reporters
+            .iter()
+            .map(|reporter| reporter.rate_per_second(elapsed))
+            .sum()
+    }
+}
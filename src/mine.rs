@@ -1,19 +1,25 @@
 use std::{
     io::{stdout, Write},
-    sync::{atomic::AtomicBool, Arc, Mutex},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
 };
 
 use ore::{self, state::Bus, BUS_ADDRESSES, BUS_COUNT, EPOCH_DURATION};
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 use solana_program::{keccak::HASH_BYTES, program_memory::sol_memcmp, pubkey::Pubkey};
 use solana_sdk::{
-    compute_budget::ComputeBudgetInstruction,
-    keccak::{hashv, Hash as KeccakHash},
-    signature::Signer,
+    compute_budget::ComputeBudgetInstruction, keccak::Hash as KeccakHash, signature::Signer,
+    transaction::Transaction,
 };
 
 use crate::{
     cu_limits::{CU_LIMIT_MINE, CU_LIMIT_RESET},
+    difficulty::difficulty_value,
+    metrics::Metrics,
     utils::{get_clock_account, get_proof, get_treasury},
     Miner,
 };
@@ -21,6 +27,20 @@ use crate::{
 // Odds of being selected to submit a reset tx
 const RESET_ODDS: u64 = 20;
 
+// Time to wait for the fee or reward to change before retrying an unprofitable submission
+const UNPROFITABLE_RETRY_DELAY: Duration = Duration::from_secs(5);
+
+// How often the search checks on its threads, and checkpoints their progress
+const SEARCH_POLL_DELAY: Duration = Duration::from_millis(100);
+const NONCE_CHECKPOINT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Where each thread stopped searching a challenge, so a restart can resume the search.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct NonceRange {
+    pub challenge: String,
+    pub next_nonces: Vec<u64>,
+}
+
 impl Miner {
     pub async fn mine(&self, threads: u64) {
         // Register, if needed.
@@ -28,8 +48,14 @@ impl Miner {
         self.register().await;
         let mut stdout = stdout();
         let mut rng = rand::thread_rng();
+        let beneficiary = if self.claim_on_confirm {
+            Some(self.initialize_ata().await)
+        } else {
+            None
+        };
 
         // Start mining loop
+        self.spawn_slot_race_detector();
         loop {
             // Fetch account state
             let balance = self.get_ore_display_balance().await;
@@ -43,6 +69,20 @@ impl Miner {
             println!("Balance: {} ORE", balance);
             println!("Claimable: {} ORE", rewards);
             println!("Reward rate: {} ORE", reward_rate);
+            {
+                let mut difficulty_predictor = self
+                    .difficulty_predictor
+                    .lock()
+                    .expect("failed to lock mutex");
+                difficulty_predictor.update(difficulty_value(&treasury.difficulty.into()));
+                println!(
+                    "Predicted difficulty: {} hashes",
+                    difficulty_predictor.predict_next()
+                );
+            }
+            if self.fee_tier_model_info {
+                self.print_fee_tier_model();
+            }
 
             // Escape sequence that clears the screen and the scrollback buffer
             println!("\nMining for a valid hash...");
@@ -52,6 +92,7 @@ impl Miner {
             // Submit mine tx.
             // Use busses randomly so on each epoch, transactions don't pile on the same busses
             println!("\n\nSubmitting hash for validation...");
+            let mut skipped = false;
             'submit: loop {
                 // Double check we're submitting for the right challenge
                 let proof_ = get_proof(&self.rpc_client, signer.pubkey()).await;
@@ -76,8 +117,9 @@ impl Miner {
                         println!("Sending epoch reset transaction...");
                         let cu_limit_ix =
                             ComputeBudgetInstruction::set_compute_unit_limit(CU_LIMIT_RESET);
-                        let cu_price_ix =
-                            ComputeBudgetInstruction::set_compute_unit_price(self.priority_fee);
+                        let cu_price_ix = ComputeBudgetInstruction::set_compute_unit_price(
+                            self.scheduled_priority_fee(),
+                        );
                         let reset_ix = ore::instruction::reset(signer.pubkey());
                         self.send_and_confirm(&[cu_limit_ix, cu_price_ix, reset_ix], false, true)
                             .await
@@ -89,21 +131,88 @@ impl Miner {
                 let bus = self.find_bus_id(treasury.reward_rate).await;
                 let bus_rewards = (bus.rewards as f64) / (10f64.powf(ore::TOKEN_DECIMALS as f64));
                 println!("Sending on bus {} ({} ORE)", bus.id, bus_rewards);
-                let cu_limit_ix = ComputeBudgetInstruction::set_compute_unit_limit(CU_LIMIT_MINE);
+                let cu_limit_ix = ComputeBudgetInstruction::set_compute_unit_limit(
+                    CU_LIMIT_MINE.saturating_add(self.memo_compute_units()),
+                );
                 let cu_price_ix =
-                    ComputeBudgetInstruction::set_compute_unit_price(self.priority_fee);
-                let ix_mine = ore::instruction::mine(
+                    ComputeBudgetInstruction::set_compute_unit_price(self.scheduled_priority_fee());
+                let mut ix_mine = ore::instruction::mine(
                     signer.pubkey(),
                     BUS_ADDRESSES[bus.id as usize],
                     next_hash.into(),
                     nonce,
                 );
-                match self
-                    .send_and_confirm(&[cu_limit_ix, cu_price_ix, ix_mine], false, false)
-                    .await
-                {
-                    Ok(sig) => {
-                        println!("Success: {}", sig);
+                ix_mine.data = self
+                    .instruction_encoding
+                    .encoder("mine")
+                    .encode(&ix_mine.data);
+                if let Some(filter) = self.profitability_filter.as_ref() {
+                    let estimated_fee = self.cost_model.predict(&Transaction::new_with_payer(
+                        &[cu_limit_ix.clone(), cu_price_ix.clone(), ix_mine.clone()],
+                        Some(&signer.pubkey()),
+                    ));
+                    if !filter.is_profitable(treasury.reward_rate, estimated_fee) {
+                        println!(
+                            "Skipping unprofitable submission: fee of {} lamports exceeds the reward by {:.0} lamports",
+                            estimated_fee,
+                            estimated_fee as f64 - filter.reward_lamports(treasury.reward_rate)
+                        );
+                        if !skipped {
+                            self.metrics
+                                .submissions_skipped
+                                .fetch_add(1, Ordering::Relaxed);
+                            skipped = true;
+                        }
+                        // The hash stays valid until the proof changes, so keep it and retry
+                        tokio::time::sleep(UNPROFITABLE_RETRY_DELAY).await;
+                        continue 'submit;
+                    }
+                }
+                // Claim the existing rewards plus the reward this submission earns
+                let claim_ixs = beneficiary.map(|beneficiary| {
+                    self.claim_ixs(
+                        signer.pubkey(),
+                        beneficiary,
+                        proof_
+                            .claimable_rewards
+                            .saturating_add(treasury.reward_rate),
+                    )
+                });
+                let res = if self.gas_auction_tiers.is_empty() {
+                    let mut mine_ixs = vec![cu_limit_ix, cu_price_ix, ix_mine];
+                    mine_ixs.extend(self.memo_ix());
+                    let mut txs_ixs = vec![mine_ixs];
+                    txs_ixs.extend(claim_ixs);
+                    match self.precompute_transactions(txs_ixs, false).await {
+                        Ok(txs) => self
+                            .submit_precomputed(txs, false)
+                            .await
+                            .map(|batch| batch.signatures),
+                        Err(err) => Err(err),
+                    }
+                } else {
+                    let mut mine_ixs = vec![cu_limit_ix, ix_mine];
+                    mine_ixs.extend(self.memo_ix());
+                    match self
+                        .send_gas_auction(&mine_ixs, &self.gas_auction_tiers)
+                        .await
+                    {
+                        Ok(sig) => match claim_ixs {
+                            Some(claim_ixs) => self
+                                .send_and_confirm(&claim_ixs, false, false)
+                                .await
+                                .map(|claim_sig| vec![sig, claim_sig]),
+                            None => Ok(vec![sig]),
+                        },
+                        Err(err) => Err(err),
+                    }
+                };
+                match res {
+                    Ok(sigs) => {
+                        println!("Success: {}", self.signature_display.format(&sigs[0]));
+                        if let Some(claim_sig) = sigs.get(1) {
+                            println!("Claimed: {}", self.signature_display.format(claim_sig));
+                        }
                         break;
                     }
                     Err(_err) => {
@@ -131,15 +240,15 @@ impl Miner {
         let mut next_hash: KeccakHash;
         let mut nonce = 0u64;
         loop {
-            next_hash = hashv(&[
-                hash.to_bytes().as_slice(),
-                signer.pubkey().to_bytes().as_slice(),
-                nonce.to_le_bytes().as_slice(),
-            ]);
+            next_hash = KeccakHash::new_from_array(self.hash_function.hash_nonce(
+                &hash.to_bytes(),
+                &signer.pubkey(),
+                nonce,
+            ));
             if next_hash.le(&difficulty) {
                 break;
             } else {
-                println!("Invalid hash: {} Nonce: {:?}", next_hash.to_string(), nonce);
+                println!("Invalid hash: {} Nonce: {:?}", next_hash, nonce);
             }
             nonce += 1;
         }
@@ -159,42 +268,67 @@ impl Miner {
         )));
         let signer = self.signer();
         let pubkey = signer.pubkey();
+        for reporter in self.hash_rate_reporters.iter() {
+            reporter.reset();
+        }
+        let started_at = Instant::now();
+        let hash_function = self.hash_function;
+        // Pick up where a checkpointed search of the same challenge stopped
+        let start_nonces = match self
+            .nonce_range
+            .lock()
+            .expect("failed to lock mutex")
+            .as_ref()
+        {
+            Some(range)
+                if range.challenge == hash.to_string()
+                    && range.next_nonces.len() as u64 == threads =>
+            {
+                range.next_nonces.clone()
+            }
+            _ => (0..threads)
+                .map(|i| u64::MAX.saturating_div(threads).saturating_mul(i))
+                .collect(),
+        };
+        // Where each thread has got to, published as it searches
+        let progress: Arc<Vec<AtomicU64>> =
+            Arc::new(start_nonces.iter().copied().map(AtomicU64::new).collect());
         let thread_handles: Vec<_> = (0..threads)
             .map(|i| {
+                let start_nonce = start_nonces[i as usize];
                 std::thread::spawn({
                     let found_solution = found_solution.clone();
+                    let progress = progress.clone();
                     let solution = solution.clone();
+                    let reporter = self.hash_rate_reporters.get(i as usize).cloned();
                     let mut stdout = stdout();
                     move || {
-                        let n = u64::MAX.saturating_div(threads).saturating_mul(i);
                         let mut next_hash: KeccakHash;
-                        let mut nonce: u64 = n;
+                        let mut nonce: u64 = start_nonce;
                         loop {
-                            next_hash = hashv(&[
-                                hash.to_bytes().as_slice(),
-                                pubkey.to_bytes().as_slice(),
-                                nonce.to_le_bytes().as_slice(),
-                            ]);
+                            next_hash = KeccakHash::new_from_array(hash_function.hash_nonce(
+                                &hash.to_bytes(),
+                                &pubkey,
+                                nonce,
+                            ));
+                            if let Some(reporter) = reporter.as_ref() {
+                                reporter.record_hash();
+                            }
                             if nonce % 10_000 == 0 {
+                                progress[i as usize].store(nonce, Ordering::Relaxed);
                                 if found_solution.load(std::sync::atomic::Ordering::Relaxed) {
-                                    return;
+                                    return nonce;
                                 }
-                                if n == 0 {
-                                    stdout
-                                        .write_all(
-                                            format!("\r{}", next_hash.to_string()).as_bytes(),
-                                        )
-                                        .ok();
+                                if i == 0 {
+                                    stdout.write_all(format!("\r{}", next_hash).as_bytes()).ok();
                                 }
                             }
                             if next_hash.le(&difficulty) {
-                                stdout
-                                    .write_all(format!("\r{}", next_hash.to_string()).as_bytes())
-                                    .ok();
+                                stdout.write_all(format!("\r{}", next_hash).as_bytes()).ok();
                                 found_solution.store(true, std::sync::atomic::Ordering::Relaxed);
                                 let mut w_solution = solution.lock().expect("failed to lock mutex");
                                 *w_solution = (next_hash, nonce);
-                                return;
+                                return nonce;
                             }
                             nonce += 1;
                         }
@@ -203,14 +337,56 @@ impl Miner {
             })
             .collect();
 
-        for thread_handle in thread_handles {
-            thread_handle.join().unwrap();
+        // Checkpoint the search as it runs, so a crash can resume this challenge
+        let mut checkpointed_at = Instant::now();
+        while !thread_handles.iter().all(|handle| handle.is_finished()) {
+            std::thread::sleep(SEARCH_POLL_DELAY);
+            if checkpointed_at.elapsed() >= NONCE_CHECKPOINT_INTERVAL {
+                self.publish_nonce_range(hash, progress.iter().map(|n| n.load(Ordering::Relaxed)));
+                self.save_checkpoint();
+                checkpointed_at = Instant::now();
+            }
         }
+        let next_nonces: Vec<u64> = thread_handles
+            .into_iter()
+            .map(|thread_handle| thread_handle.join().unwrap())
+            .collect();
+        self.publish_nonce_range(hash, next_nonces.into_iter());
+        self.print_hash_rates(started_at.elapsed());
 
         let r_solution = solution.lock().expect("Failed to get lock");
         *r_solution
     }
 
+    fn publish_nonce_range(&self, hash: KeccakHash, next_nonces: impl Iterator<Item = u64>) {
+        *self.nonce_range.lock().expect("failed to lock mutex") = Some(NonceRange {
+            challenge: hash.to_string(),
+            next_nonces: next_nonces.collect(),
+        });
+    }
+
+    fn print_hash_rates(&self, elapsed: Duration) {
+        if self.hash_rate_reporters.is_empty() {
+            return;
+        }
+        let per_thread: Vec<String> = self
+            .hash_rate_reporters
+            .iter()
+            .map(|reporter| {
+                format!(
+                    "#{} {:.0}",
+                    reporter.thread_id,
+                    reporter.rate_per_second(elapsed)
+                )
+            })
+            .collect();
+        println!(
+            "\nHash rate: {:.0} H/s ({})",
+            Metrics::total_hashes_per_second(&self.hash_rate_reporters, elapsed),
+            per_thread.join(", ")
+        );
+    }
+
     pub fn validate_hash(
         &self,
         hash: KeccakHash,
@@ -220,11 +396,9 @@ impl Miner {
         difficulty: KeccakHash,
     ) -> bool {
         // Validate hash correctness
-        let hash_ = hashv(&[
-            current_hash.as_ref(),
-            signer.as_ref(),
-            nonce.to_le_bytes().as_slice(),
-        ]);
+        let hash_ = self
+            .hash_function
+            .hash_nonce(&current_hash.to_bytes(), &signer, nonce);
         if sol_memcmp(hash.as_ref(), hash_.as_ref(), HASH_BYTES) != 0 {
             return false;
         }
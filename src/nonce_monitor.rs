@@ -0,0 +1,119 @@
+use futures::{channel::mpsc, StreamExt};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    client_error::{ClientError, ClientErrorKind, Result as ClientResult},
+    nonblocking::pubsub_client::PubsubClient,
+    rpc_config::RpcAccountInfoConfig,
+};
+use solana_program::{hash::Hash, pubkey::Pubkey};
+use solana_sdk::{
+    account::Account,
+    nonce::state::{State, Versions},
+};
+use tokio::sync::oneshot;
+
+use crate::{error::MinerError, Miner};
+
+fn pubsub_error(err: impl ToString) -> ClientError {
+    ClientError {
+        request: None,
+        kind: ClientErrorKind::Custom(format!(
+            "Nonce account subscription failed: {}",
+            err.to_string()
+        )),
+    }
+}
+
+/// The nonce stored in a durable nonce account, if it is initialized.
+fn stored_nonce(account: &Account) -> Option<Hash> {
+    let versions: Versions = bincode::deserialize(&account.data).ok()?;
+    match versions.state() {
+        State::Initialized(data) => Some(data.blockhash()),
+        State::Uninitialized => None,
+    }
+}
+
+/// Watches a durable nonce account over `accountSubscribe`, so a transaction built on
+/// its nonce can be given up on as soon as another transaction advances it.
+pub struct NonceAccountHealthMonitor {
+    expected: Hash,
+    stored: Hash,
+    nonces: mpsc::UnboundedReceiver<Hash>,
+}
+
+impl NonceAccountHealthMonitor {
+    /// Resolves with `MinerError::NonceConsumed` once the stored nonce differs from the
+    /// one the transaction was built on, and never if the subscription ends first.
+    pub async fn consumed(&mut self) -> MinerError {
+        if self.stored != self.expected {
+            return MinerError::NonceConsumed {
+                new_nonce: self.stored,
+            };
+        }
+        while let Some(nonce) = self.nonces.next().await {
+            if nonce != self.expected {
+                return MinerError::NonceConsumed { new_nonce: nonce };
+            }
+        }
+        futures::future::pending().await
+    }
+}
+
+impl Miner {
+    /// Subscribes to `account`, then reads the nonce it stores now, so no change away from
+    /// `expected`, the nonce a pending transaction was built on, is missed.
+    pub async fn watch_nonce_account(
+        &self,
+        account: Pubkey,
+        expected: Hash,
+    ) -> ClientResult<NonceAccountHealthMonitor> {
+        let client = PubsubClient::new(&self.rpc_ws_url)
+            .await
+            .map_err(pubsub_error)?;
+        let config = RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(self.rpc_client.commitment()),
+            ..RpcAccountInfoConfig::default()
+        };
+
+        // The subscription borrows the client, so both live in a task that forwards
+        // nonces until the monitor is dropped.
+        let (sender, nonces) = mpsc::unbounded();
+        let (subscribed, subscription) = oneshot::channel();
+        tokio::spawn(async move {
+            let (mut notifications, unsubscribe) =
+                match client.account_subscribe(&account, Some(config)).await {
+                    Ok(subscription) => subscription,
+                    Err(err) => {
+                        subscribed.send(Err(pubsub_error(err))).ok();
+                        return;
+                    }
+                };
+            subscribed.send(Ok(())).ok();
+            while let Some(response) = notifications.next().await {
+                let Some(nonce) = response.value.decode().as_ref().and_then(stored_nonce) else {
+                    continue;
+                };
+                if sender.unbounded_send(nonce).is_err() {
+                    break;
+                }
+            }
+            unsubscribe().await;
+        });
+        subscription
+            .await
+            .map_err(|_| pubsub_error("subscription task exited"))??;
+
+        let info = self
+            .guarded_rpc(self.rpc_client.get_account(&account))
+            .await?;
+        let stored = stored_nonce(&info).ok_or_else(|| {
+            pubsub_error(format!("{} is not an initialized nonce account", account))
+        })?;
+        Ok(NonceAccountHealthMonitor {
+            expected,
+            stored,
+            nonces,
+        })
+    }
+}
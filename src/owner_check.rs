@@ -0,0 +1,74 @@
+use ore::{instruction::OreInstruction, BUS_ADDRESSES, MINT_ADDRESS, TREASURY_ADDRESS};
+use solana_client::client_error::Result as ClientResult;
+use solana_program::{instruction::Instruction, pubkey::Pubkey, system_program};
+
+use crate::{
+    error::MinerError,
+    utils::{proof_pubkey, treasury_tokens_pubkey},
+    Miner,
+};
+
+/// The program that must own `account` for an Ore instruction signed by `authority` to
+/// succeed, if it is one of the accounts the Ore program reads or writes.
+fn expected_owner(account: &Pubkey, authority: &Pubkey) -> Option<Pubkey> {
+    if BUS_ADDRESSES.contains(account)
+        || *account == TREASURY_ADDRESS
+        || *account == proof_pubkey(*authority)
+    {
+        Some(ore::ID)
+    } else if *account == MINT_ADDRESS || *account == treasury_tokens_pubkey() {
+        Some(spl_token::id())
+    } else {
+        None
+    }
+}
+
+/// Accounts in Ore instructions paired with the program expected to own them.
+fn expected_owners(ixs: &[Instruction]) -> Vec<(Pubkey, Pubkey)> {
+    let mut expected: Vec<(Pubkey, Pubkey)> = vec![];
+    for ix in ixs.iter().filter(|ix| ix.program_id == ore::ID) {
+        // Registering creates the proof, so it is not expected to exist yet
+        if ix.data.first() == Some(&(OreInstruction::Register as u8)) {
+            continue;
+        }
+        let Some(authority) = ix.accounts.iter().find(|meta| meta.is_signer) else {
+            continue;
+        };
+        for meta in ix.accounts.iter() {
+            if let Some(owner) = expected_owner(&meta.pubkey, &authority.pubkey) {
+                if !expected.iter().any(|(account, _)| *account == meta.pubkey) {
+                    expected.push((meta.pubkey, owner));
+                }
+            }
+        }
+    }
+    expected
+}
+
+impl Miner {
+    /// Fails with `MinerError::UnexpectedAccountOwner` if an account the Ore program
+    /// expects to own is missing or owned by another program.
+    pub async fn check_account_owners(&self, ixs: &[Instruction]) -> ClientResult<()> {
+        let expected = expected_owners(ixs);
+        if expected.is_empty() {
+            return Ok(());
+        }
+        let pubkeys: Vec<Pubkey> = expected.iter().map(|(account, _)| *account).collect();
+        let accounts = self
+            .guarded_rpc(self.rpc_client.get_multiple_accounts(&pubkeys))
+            .await?;
+        for ((account, expected), info) in expected.into_iter().zip(accounts) {
+            // Accounts that were never initialized belong to the system program
+            let found = info.map_or(system_program::id(), |info| info.owner);
+            if found != expected {
+                return Err(MinerError::UnexpectedAccountOwner {
+                    account,
+                    expected,
+                    found,
+                }
+                .into());
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,172 @@
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+};
+
+use ore::{instruction::OreInstruction, EPOCH_DURATION};
+use solana_client::{
+    client_error::{ClientError, ClientErrorKind, Result as ClientResult},
+    nonblocking::rpc_client::RpcClient,
+    rpc_client::GetConfirmedSignaturesForAddress2Config,
+    rpc_config::RpcTransactionConfig,
+};
+use solana_program::{keccak::Hash as KeccakHash, pubkey::Pubkey};
+use solana_sdk::{commitment_config::CommitmentConfig, signature::Signature};
+use solana_transaction_status::UiTransactionEncoding;
+
+use crate::{utils::get_treasury, Miner};
+
+/// Activity of one account among recent successful mine submissions.
+#[derive(Clone, Debug)]
+pub struct PeerMiner {
+    pub signer: Pubkey,
+    pub submissions: usize,
+    pub epochs: usize,
+    pub hash_rate: f64,
+    pub share: f64,
+}
+
+/// Estimates the hash rate of competing miners from recent successful submissions to the
+/// Ore program.
+pub struct PeerMinerDetector {
+    limit: usize,
+}
+
+impl PeerMinerDetector {
+    pub fn new(limit: usize) -> Self {
+        Self { limit }
+    }
+
+    /// Returns peers ordered by submissions, along with the number of epochs the
+    /// submissions span.
+    pub async fn detect(
+        &self,
+        client: &RpcClient,
+        difficulty: KeccakHash,
+    ) -> ClientResult<(Vec<PeerMiner>, usize)> {
+        let config = GetConfirmedSignaturesForAddress2Config {
+            limit: Some(self.limit),
+            commitment: Some(CommitmentConfig::confirmed()),
+            ..Default::default()
+        };
+        let statuses = client
+            .get_signatures_for_address_with_config(&ore::ID, config)
+            .await?;
+
+        let mut submissions: Vec<(Pubkey, i64)> = vec![];
+        for status in statuses.iter().filter(|status| status.err.is_none()) {
+            let Some(block_time) = status.block_time else {
+                continue;
+            };
+            let sig = Signature::from_str(&status.signature).map_err(|err| ClientError {
+                request: None,
+                kind: ClientErrorKind::Custom(format!("Invalid signature: {}", err)),
+            })?;
+            if let Some(signer) = mine_signer(client, &sig).await? {
+                submissions.push((signer, block_time));
+            }
+        }
+        if submissions.is_empty() {
+            return Ok((vec![], 0));
+        }
+
+        // Each submission is one hash at or under the difficulty, so it stands for the
+        // expected number of attempts needed to find one
+        let hashes_per_submission = 2f64.powi(256) / (hash_value(&difficulty) + 1.0);
+        let (first, last) = submissions
+            .iter()
+            .fold((i64::MAX, i64::MIN), |(first, last), (_, time)| {
+                (first.min(*time), last.max(*time))
+            });
+        let window_secs = (last - first).max(1) as f64;
+        let epoch_count = submissions
+            .iter()
+            .map(|(_, time)| time / EPOCH_DURATION)
+            .collect::<HashSet<_>>()
+            .len();
+
+        let mut by_signer: HashMap<Pubkey, Vec<i64>> = HashMap::new();
+        for (signer, time) in submissions.iter() {
+            by_signer.entry(*signer).or_default().push(*time);
+        }
+        let total = submissions.len() as f64;
+        let mut peers: Vec<PeerMiner> = by_signer
+            .into_iter()
+            .map(|(signer, times)| PeerMiner {
+                signer,
+                submissions: times.len(),
+                epochs: times
+                    .iter()
+                    .map(|time| time / EPOCH_DURATION)
+                    .collect::<HashSet<_>>()
+                    .len(),
+                hash_rate: times.len() as f64 * hashes_per_submission / window_secs,
+                share: times.len() as f64 / total,
+            })
+            .collect();
+        peers.sort_by(|a, b| b.submissions.cmp(&a.submissions));
+        Ok((peers, epoch_count))
+    }
+}
+
+/// Returns the signer of the mine instruction in a transaction, if it has one.
+async fn mine_signer(client: &RpcClient, sig: &Signature) -> ClientResult<Option<Pubkey>> {
+    let config = RpcTransactionConfig {
+        encoding: Some(UiTransactionEncoding::Base64),
+        commitment: Some(CommitmentConfig::confirmed()),
+        max_supported_transaction_version: Some(0),
+    };
+    let tx = client.get_transaction_with_config(sig, config).await?;
+    let Some(tx) = tx.transaction.transaction.decode() else {
+        return Ok(None);
+    };
+    let keys = tx.message.static_account_keys();
+    Ok(tx.message.instructions().iter().find_map(|ix| {
+        let is_mine = keys.get(ix.program_id_index as usize) == Some(&ore::ID)
+            && ix.data.first() == Some(&(OreInstruction::Mine as u8));
+        is_mine
+            .then(|| ix.accounts.first())
+            .flatten()
+            .and_then(|index| keys.get(*index as usize))
+            .copied()
+    }))
+}
+
+/// Interprets a hash as a big-endian integer, the order hashes are compared in.
+fn hash_value(hash: &KeccakHash) -> f64 {
+    hash.to_bytes()
+        .iter()
+        .fold(0f64, |value, byte| value * 256.0 + *byte as f64)
+}
+
+impl Miner {
+    pub async fn peers(&self, limit: usize) {
+        let treasury = get_treasury(&self.rpc_client).await;
+        let detector = PeerMinerDetector::new(limit);
+        let (peers, epochs) = match detector
+            .detect(&self.rpc_client, treasury.difficulty.into())
+            .await
+        {
+            Ok(detected) => detected,
+            Err(err) => {
+                println!("Error: {}", err);
+                return;
+            }
+        };
+        println!(
+            "{} miners submitted across {} epochs ({:.1} per epoch)",
+            peers.len(),
+            epochs,
+            peers.iter().map(|peer| peer.epochs).sum::<usize>() as f64 / epochs.max(1) as f64
+        );
+        for peer in peers {
+            println!(
+                "{}: {} submissions, {:.1}% share, ~{:.0} H/s",
+                peer.signer,
+                peer.submissions,
+                peer.share * 100.0,
+                peer.hash_rate
+            );
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use std::{collections::HashMap, sync::Mutex, time::Duration};
+
+use solana_client::{
+    nonblocking::rpc_client::RpcClient, rpc_request::MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS,
+};
+use solana_sdk::{commitment_config::CommitmentConfig, signature::Signature};
+
+use crate::lifecycle::batch_println;
+
+const PENDING_POLL_DELAY: u64 = 200;
+
+/// Caps how many sent transactions may await confirmation at once. A signature counts as
+/// pending from when it is sent until it confirms or its blockhash expires, whichever task
+/// sent it and whether or not that task waits for it.
+pub struct PendingSignatureLimit {
+    max_pending: usize,
+    /// Pending signatures and the last block height their blockhash is valid at
+    pending: Mutex<HashMap<Signature, u64>>,
+}
+
+impl PendingSignatureLimit {
+    pub fn new(max_pending: usize) -> Self {
+        Self {
+            max_pending: max_pending.max(1),
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.pending.lock().expect("failed to lock mutex").len()
+    }
+
+    pub fn track(&self, sig: Signature, last_valid_block_height: u64) {
+        self.pending
+            .lock()
+            .expect("failed to lock mutex")
+            .insert(sig, last_valid_block_height);
+    }
+
+    /// Stops counting a signature its sender saw confirm.
+    pub fn resolve(&self, sig: &Signature) {
+        self.pending
+            .lock()
+            .expect("failed to lock mutex")
+            .remove(sig);
+    }
+
+    /// Waits until fewer than the maximum number of signatures are pending, checking
+    /// whether any have confirmed or expired meanwhile.
+    pub async fn wait_for_capacity(&self, client: &RpcClient) {
+        let mut waiting = false;
+        loop {
+            if self.len() < self.max_pending {
+                return;
+            }
+            self.prune(client).await;
+            let pending = self.len();
+            if pending < self.max_pending {
+                return;
+            }
+            if !waiting {
+                batch_println!("Waiting for {} pending signatures to confirm", pending);
+                waiting = true;
+            }
+            tokio::time::sleep(Duration::from_millis(PENDING_POLL_DELAY)).await;
+        }
+    }
+
+    /// Drops signatures that confirmed or whose blockhash expired. Expiry is checked even
+    /// if statuses cannot be fetched, so the set cannot grow without bound.
+    async fn prune(&self, client: &RpcClient) {
+        let sigs: Vec<Signature> = self
+            .pending
+            .lock()
+            .expect("failed to lock mutex")
+            .keys()
+            .copied()
+            .collect();
+        let mut confirmed = vec![];
+        for chunk in sigs.chunks(MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS) {
+            let Ok(statuses) = client.get_signature_statuses(chunk).await else {
+                continue;
+            };
+            for (sig, status) in chunk.iter().zip(statuses.value) {
+                if status.is_some_and(|status| {
+                    status.satisfies_commitment(CommitmentConfig::confirmed())
+                }) {
+                    confirmed.push(*sig);
+                }
+            }
+        }
+        let block_height = client.get_block_height().await.ok();
+        let mut pending = self.pending.lock().expect("failed to lock mutex");
+        for sig in confirmed.iter() {
+            pending.remove(sig);
+        }
+        if let Some(block_height) = block_height {
+            pending.retain(|_, last_valid_block_height| *last_valid_block_height >= block_height);
+        }
+    }
+}
@@ -0,0 +1,24 @@
+use ore::TOKEN_DECIMALS;
+
+/// Predicts whether a submission pays for itself, valuing ORE at a fixed price in lamports.
+#[derive(Clone, Copy, Debug)]
+pub struct ProfitabilityFilter {
+    lamports_per_ore: f64,
+}
+
+impl ProfitabilityFilter {
+    pub fn new(lamports_per_ore: f64) -> Self {
+        Self { lamports_per_ore }
+    }
+
+    /// Converts a reward in the smallest ORE unit into lamports.
+    pub fn reward_lamports(&self, reward: u64) -> f64 {
+        (reward as f64) / 10f64.powf(TOKEN_DECIMALS as f64) * self.lamports_per_ore
+    }
+
+    /// Returns whether `expected_reward`, in the smallest ORE unit, covers `estimated_fee`
+    /// in lamports.
+    pub fn is_profitable(&self, expected_reward: u64, estimated_fee: u64) -> bool {
+        self.reward_lamports(expected_reward) >= estimated_fee as f64
+    }
+}
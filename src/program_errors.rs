@@ -0,0 +1,36 @@
+use std::{collections::HashMap, path::Path};
+
+use solana_sdk::{instruction::InstructionError, transaction::TransactionError};
+
+use crate::error::MinerError;
+
+/// Names for the custom error codes of the programs the miner calls, loaded from a JSON
+/// object such as `{ "5": "RewardAlreadyClaimed" }`.
+pub struct ProgramErrorDecoder {
+    error_table: HashMap<u32, String>,
+}
+
+impl ProgramErrorDecoder {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let data =
+            std::fs::read(path).map_err(|err| format!("failed to read {:?}: {}", path, err))?;
+        let error_table = serde_json::from_slice(&data)
+            .map_err(|err| format!("failed to parse {:?}: {}", path, err))?;
+        Ok(Self { error_table })
+    }
+
+    /// Returns a `MinerError::ProgramError` naming `err` if it is a custom program error
+    /// listed in the table.
+    pub fn decode(&self, err: &TransactionError) -> Option<MinerError> {
+        let TransactionError::InstructionError(instruction, InstructionError::Custom(code)) = err
+        else {
+            return None;
+        };
+        let name = self.error_table.get(code)?;
+        Some(MinerError::ProgramError {
+            instruction: *instruction,
+            code: *code,
+            name: name.clone(),
+        })
+    }
+}
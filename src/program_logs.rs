@@ -0,0 +1,80 @@
+use futures::{
+    channel::{mpsc, oneshot},
+    Stream, StreamExt,
+};
+use solana_client::{
+    client_error::{ClientError, ClientErrorKind, Result as ClientResult},
+    nonblocking::pubsub_client::PubsubClient,
+    rpc_config::{RpcTransactionLogsConfig, RpcTransactionLogsFilter},
+    rpc_response::RpcLogsResponse,
+};
+
+use crate::Miner;
+
+fn pubsub_error(err: impl ToString) -> ClientError {
+    ClientError {
+        request: None,
+        kind: ClientErrorKind::Custom(format!("Log subscription failed: {}", err.to_string())),
+    }
+}
+
+impl Miner {
+    /// Streams the logs of every transaction that mentions the Ore program.
+    pub async fn subscribe_to_program_logs(
+        &self,
+    ) -> ClientResult<impl Stream<Item = RpcLogsResponse>> {
+        let client = PubsubClient::new(&self.rpc_ws_url)
+            .await
+            .map_err(pubsub_error)?;
+        let filter = RpcTransactionLogsFilter::Mentions(vec![ore::ID.to_string()]);
+        let config = RpcTransactionLogsConfig {
+            commitment: Some(self.rpc_client.commitment()),
+        };
+
+        // The subscription borrows the client, so both live in a task that forwards
+        // notifications until the receiver is dropped.
+        let (sender, receiver) = mpsc::unbounded();
+        let (subscribed, subscription) = oneshot::channel();
+        tokio::spawn(async move {
+            let (mut logs, unsubscribe) = match client.logs_subscribe(filter, config).await {
+                Ok(subscription) => subscription,
+                Err(err) => {
+                    subscribed.send(Err(pubsub_error(err))).ok();
+                    return;
+                }
+            };
+            subscribed.send(Ok(())).ok();
+            while let Some(response) = logs.next().await {
+                if sender.unbounded_send(response.value).is_err() {
+                    break;
+                }
+            }
+            unsubscribe().await;
+        });
+        subscription
+            .await
+            .map_err(|_| pubsub_error("subscription task exited"))??;
+        Ok(receiver)
+    }
+
+    pub async fn logs(&self) {
+        let mut logs = match self.subscribe_to_program_logs().await {
+            Ok(logs) => logs,
+            Err(err) => {
+                eprintln!("Error: {}", err);
+                return;
+            }
+        };
+        while let Some(response) = logs.next().await {
+            let status = if response.err.is_some() {
+                "failed"
+            } else {
+                "ok"
+            };
+            println!("{} ({})", response.signature, status);
+            for log in response.logs.iter() {
+                println!("  {}", log);
+            }
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use std::{collections::HashMap, sync::Mutex, time::Duration};
+
+use solana_client::{
+    nonblocking::rpc_client::RpcClient, rpc_config::RpcSendTransactionConfig,
+    rpc_request::MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS,
+};
+use solana_sdk::{signature::Signature, transaction::VersionedTransaction};
+
+use crate::{transaction::MinerTransaction, Miner};
+
+struct PendingTransaction {
+    tx: VersionedTransaction,
+    last_valid_block_height: u64,
+    config: RpcSendTransactionConfig,
+}
+
+/// Re-sends signed transactions that have not landed yet, as long as their blockhash
+/// is still valid, in case an RPC node stopped forwarding them to the leader.
+pub struct TransactionRebroadcaster {
+    interval: Duration,
+    pending: Mutex<HashMap<Signature, PendingTransaction>>,
+}
+
+impl TransactionRebroadcaster {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Rebroadcasts `tx` until it lands or its blockhash expires.
+    pub fn track(
+        &self,
+        sig: Signature,
+        tx: &impl MinerTransaction,
+        last_valid_block_height: u64,
+        config: RpcSendTransactionConfig,
+    ) {
+        // A legacy transaction serializes the same as a legacy versioned one
+        let Ok(tx) = bincode::serialize(tx).and_then(|data| bincode::deserialize(&data)) else {
+            return;
+        };
+        self.pending.lock().expect("failed to lock mutex").insert(
+            sig,
+            PendingTransaction {
+                tx,
+                last_valid_block_height,
+                config,
+            },
+        );
+    }
+
+    /// Stops rebroadcasting `sig`, for a transaction that should no longer land.
+    pub fn cancel(&self, sig: &Signature) {
+        self.pending
+            .lock()
+            .expect("failed to lock mutex")
+            .remove(sig);
+    }
+
+    /// Drops transactions that have landed or expired and re-sends the rest. Expired
+    /// transactions are dropped even if their statuses could not be fetched.
+    async fn rebroadcast(&self, client: &RpcClient) {
+        let sigs: Vec<Signature> = self
+            .pending
+            .lock()
+            .expect("failed to lock mutex")
+            .keys()
+            .copied()
+            .collect();
+        if sigs.is_empty() {
+            return;
+        }
+        let mut landed = vec![];
+        for chunk in sigs.chunks(MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS) {
+            let Ok(statuses) = client.get_signature_statuses(chunk).await else {
+                continue;
+            };
+            for (sig, status) in chunk.iter().zip(statuses.value) {
+                if status.is_some() {
+                    landed.push(*sig);
+                }
+            }
+        }
+        let block_height = client.get_block_height().await.ok();
+
+        let resend: Vec<(VersionedTransaction, RpcSendTransactionConfig)> = {
+            let mut pending = self.pending.lock().expect("failed to lock mutex");
+            for sig in landed.iter() {
+                pending.remove(sig);
+            }
+            if let Some(block_height) = block_height {
+                pending.retain(|_, tx| tx.last_valid_block_height >= block_height);
+            }
+            pending
+                .values()
+                .map(|pending| (pending.tx.clone(), pending.config))
+                .collect()
+        };
+        for (tx, config) in resend.iter() {
+            if let Err(err) = client.send_transaction_with_config(tx, *config).await {
+                println!("Error rebroadcasting transaction: {}", err);
+            }
+        }
+    }
+}
+
+impl Miner {
+    /// Rebroadcasts pending transactions every `--rebroadcast-interval` seconds.
+    pub fn spawn_rebroadcaster(&self) {
+        let Some(rebroadcaster) = self.rebroadcaster.clone() else {
+            return;
+        };
+        let client = self.rpc_client.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(rebroadcaster.interval).await;
+                rebroadcaster.rebroadcast(&client).await;
+            }
+        });
+    }
+}
@@ -0,0 +1,60 @@
+use std::collections::HashMap;
+
+use solana_client::client_error::Result as ClientResult;
+use solana_program::{instruction::Instruction, pubkey::Pubkey};
+
+use crate::{error::MinerError, Miner};
+
+/// Every account the instructions write to, in order of first use.
+fn writable_accounts(ixs: &[Instruction]) -> Vec<Pubkey> {
+    let mut writable: Vec<Pubkey> = vec![];
+    for meta in ixs.iter().flat_map(|ix| ix.accounts.iter()) {
+        if meta.is_writable && !writable.contains(&meta.pubkey) {
+            writable.push(meta.pubkey);
+        }
+    }
+    writable
+}
+
+impl Miner {
+    /// Fails with `MinerError::AccountNotRentExempt` if a writable account holds less
+    /// than the rent-exempt minimum for its current data size.
+    pub async fn check_rent_exemption(&self, ixs: &[Instruction]) -> ClientResult<()> {
+        let pubkeys = writable_accounts(ixs);
+        if pubkeys.is_empty() {
+            return Ok(());
+        }
+        let accounts = self
+            .guarded_rpc(self.rpc_client.get_multiple_accounts(&pubkeys))
+            .await?;
+        let mut minimums: HashMap<usize, u64> = HashMap::new();
+        for (account, info) in pubkeys.into_iter().zip(accounts) {
+            // Accounts that do not exist yet are funded by the instruction creating them
+            let Some(info) = info else {
+                continue;
+            };
+            let minimum = match minimums.get(&info.data.len()) {
+                Some(minimum) => *minimum,
+                None => {
+                    let minimum = self
+                        .guarded_rpc(
+                            self.rpc_client
+                                .get_minimum_balance_for_rent_exemption(info.data.len()),
+                        )
+                        .await?;
+                    minimums.insert(info.data.len(), minimum);
+                    minimum
+                }
+            };
+            if info.lamports < minimum {
+                return Err(MinerError::AccountNotRentExempt {
+                    account,
+                    lamports: info.lamports,
+                    minimum,
+                }
+                .into());
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,36 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::{error::MinerError, Miner};
+
+/// Caps the send retries shared by every transaction of a batch, so a batch of many
+/// transactions cannot multiply the retry load on the RPC.
+pub struct RetryBudget {
+    pub total: usize,
+    remaining: AtomicUsize,
+}
+
+impl RetryBudget {
+    pub fn new(total: usize) -> Self {
+        Self {
+            total,
+            remaining: AtomicUsize::new(total),
+        }
+    }
+
+    /// Takes one retry from the budget, failing once none are left.
+    pub fn consume(&self) -> Result<(), MinerError> {
+        self.remaining
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |remaining| {
+                remaining.checked_sub(1)
+            })
+            .map(|_| ())
+            .map_err(|_| MinerError::RetryBudgetExhausted(self.total))
+    }
+}
+
+impl Miner {
+    /// Returns a fresh budget for one batch, if `--batch-retry-budget` is set.
+    pub fn retry_budget(&self) -> Option<RetryBudget> {
+        self.batch_retry_budget.map(RetryBudget::new)
+    }
+}
@@ -0,0 +1,138 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::PathBuf,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+use solana_program::{hash::hashv, pubkey::Pubkey};
+
+use crate::Miner;
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RotationTrigger {
+    Time,
+    Count,
+}
+
+/// The fields of a rotation covered by the chained hash.
+#[derive(Debug, Deserialize, Serialize)]
+struct RotationRecord {
+    timestamp: u64,
+    old_pubkey: String,
+    new_pubkey: String,
+    trigger: RotationTrigger,
+    balance_transferred: u64,
+    prev_hash: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct RotationEntry {
+    #[serde(flatten)]
+    record: RotationRecord,
+    hash: String,
+}
+
+impl RotationRecord {
+    fn hash(&self) -> io::Result<String> {
+        // The record includes prev_hash, which is what chains it to the entry before
+        Ok(hashv(&[&serde_json::to_vec(self)?]).to_string())
+    }
+}
+
+/// Append-only JSON lines log of keypair rotations. Each entry carries the SHA-256 hash
+/// of the previous one, so editing or removing an entry breaks every hash after it.
+pub struct KeyRotationLog {
+    path: PathBuf,
+}
+
+impl KeyRotationLog {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    fn entries(&self) -> io::Result<Vec<RotationEntry>> {
+        let data = match fs::read_to_string(&self.path) {
+            Ok(data) => data,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(err) => return Err(err),
+        };
+        data.lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| serde_json::from_str(line).map_err(io::Error::from))
+            .collect()
+    }
+
+    /// The pubkey the last logged rotation rotated to, if any.
+    pub fn last_pubkey(&self) -> io::Result<Option<Pubkey>> {
+        let Some(entry) = self.entries()?.pop() else {
+            return Ok(None);
+        };
+        Pubkey::from_str(&entry.record.new_pubkey)
+            .map(Some)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    pub fn append(
+        &self,
+        old_pubkey: Pubkey,
+        new_pubkey: Pubkey,
+        trigger: RotationTrigger,
+        balance_transferred: u64,
+    ) -> io::Result<()> {
+        let prev_hash = self
+            .entries()?
+            .last()
+            .map_or(String::new(), |entry| entry.hash.clone());
+        let record = RotationRecord {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            old_pubkey: old_pubkey.to_string(),
+            new_pubkey: new_pubkey.to_string(),
+            trigger,
+            balance_transferred,
+            prev_hash,
+        };
+        let entry = RotationEntry {
+            hash: record.hash()?,
+            record,
+        };
+        let mut line = serde_json::to_vec(&entry)?;
+        line.push(b'\n');
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?
+            .write_all(&line)
+    }
+
+    /// Returns the number of entries, or the line of the first entry whose hash does not
+    /// match its contents or the entry before it.
+    pub fn verify(&self) -> io::Result<Result<usize, usize>> {
+        let entries = self.entries()?;
+        let mut prev_hash = String::new();
+        for (i, entry) in entries.iter().enumerate() {
+            if entry.record.prev_hash != prev_hash || entry.record.hash()? != entry.hash {
+                return Ok(Err(i + 1));
+            }
+            prev_hash = entry.hash.clone();
+        }
+        Ok(Ok(entries.len()))
+    }
+}
+
+impl Miner {
+    pub fn verify_rotation_log(&self, path: PathBuf) {
+        let log = KeyRotationLog::new(path);
+        match log.verify() {
+            Ok(Ok(count)) => println!("Rotation log is intact ({} entries)", count),
+            Ok(Err(line)) => println!("Rotation log was modified at line {}", line),
+            Err(err) => println!("Failed to read {:?}: {}", log.path, err),
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use async_trait::async_trait;
+use flate2::{write::GzEncoder, Compression};
+use serde_json::{json, Value};
+use solana_client::{
+    client_error::Result as ClientResult, nonblocking::rpc_client::RpcClient,
+    rpc_request::RpcRequest,
+};
+use solana_rpc_client::{
+    http_sender::HttpSender,
+    rpc_client::RpcClientConfig,
+    rpc_sender::{RpcSender, RpcTransportStats},
+};
+use solana_sdk::commitment_config::CommitmentConfig;
+
+use crate::lifecycle::TransactionGrouper;
+
+const BYTES_PER_MB: u64 = 1024 * 1024;
+
+/// A gzip-compressed JSONL file of RPC responses, moved aside to `<path>.1` once it
+/// grows past `max_bytes`.
+struct RpcResponseLog {
+    path: PathBuf,
+    max_bytes: u64,
+    encoder: Mutex<GzEncoder<File>>,
+}
+
+fn open_encoder(path: &Path) -> io::Result<GzEncoder<File>> {
+    // Each run appends a new gzip member, which gzip readers decode as one stream
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    Ok(GzEncoder::new(file, Compression::default()))
+}
+
+impl RpcResponseLog {
+    fn open(path: PathBuf, max_mb: u64) -> io::Result<Self> {
+        let encoder = open_encoder(&path)?;
+        Ok(Self {
+            path,
+            max_bytes: max_mb.saturating_mul(BYTES_PER_MB),
+            encoder: Mutex::new(encoder),
+        })
+    }
+
+    fn write(&self, entry: &Value) -> io::Result<()> {
+        let mut encoder = self.encoder.lock().expect("failed to lock mutex");
+        serde_json::to_writer(&mut *encoder, entry)?;
+        encoder.write_all(b"\n")?;
+        // Flushed per entry so a crash loses at most the entry being written
+        encoder.flush()?;
+        if encoder.get_ref().metadata()?.len() < self.max_bytes {
+            return Ok(());
+        }
+
+        let mut rotated = self.path.clone().into_os_string();
+        rotated.push(".1");
+        fs::rename(&self.path, rotated)?;
+        let full = std::mem::replace(&mut *encoder, open_encoder(&self.path)?);
+        full.finish()?;
+        Ok(())
+    }
+}
+
+/// Sends RPC requests over HTTP like the default client, logging every response.
+struct LoggingSender {
+    inner: HttpSender,
+    log: RpcResponseLog,
+}
+
+#[async_trait]
+impl RpcSender for LoggingSender {
+    async fn send(&self, request: RpcRequest, params: Value) -> ClientResult<Value> {
+        let response = self.inner.send(request, params.clone()).await;
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_millis() as u64);
+        let mut entry = json!({
+            "timestamp_ms": timestamp,
+            "method": request.to_string(),
+            "params": params,
+        });
+        if let Some(group_id) = TransactionGrouper::current() {
+            entry["group_id"] = Value::String(group_id.to_string());
+        }
+        match response.as_ref() {
+            Ok(result) => entry["result"] = result.clone(),
+            Err(err) => entry["error"] = Value::String(err.to_string()),
+        }
+        if let Err(err) = self.log.write(&entry) {
+            println!("Failed to log RPC response to {:?}: {}", self.log.path, err);
+        }
+        response
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        self.inner.get_transport_stats()
+    }
+
+    fn url(&self) -> String {
+        self.inner.url()
+    }
+}
+
+/// An RPC client that records every response it receives to `path`.
+pub fn logging_rpc_client(
+    url: String,
+    commitment: CommitmentConfig,
+    path: PathBuf,
+    max_mb: u64,
+) -> io::Result<RpcClient> {
+    let sender = LoggingSender {
+        inner: HttpSender::new(url),
+        log: RpcResponseLog::open(path, max_mb)?,
+    };
+    Ok(RpcClient::new_sender(
+        sender,
+        RpcClientConfig::with_commitment(commitment),
+    ))
+}
@@ -0,0 +1,31 @@
+use std::sync::Arc;
+
+use solana_client::nonblocking::rpc_client::RpcClient;
+
+pub struct RpcPool {
+    clients: Vec<Arc<RpcClient>>,
+}
+
+impl RpcPool {
+    pub fn new(primary: Arc<RpcClient>, urls: Vec<String>) -> Self {
+        let commitment = primary.commitment();
+        let mut clients = vec![primary];
+        clients.extend(
+            urls.into_iter()
+                .map(|url| Arc::new(RpcClient::new_with_commitment(url, commitment))),
+        );
+        Self { clients }
+    }
+
+    pub fn len(&self) -> usize {
+        self.clients.len()
+    }
+
+    pub fn clients(&self) -> &[Arc<RpcClient>] {
+        &self.clients
+    }
+
+    pub fn get(&self, index: usize) -> Arc<RpcClient> {
+        self.clients[index % self.clients.len()].clone()
+    }
+}
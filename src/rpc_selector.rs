@@ -0,0 +1,142 @@
+use std::{
+    fmt::Write as _,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+
+use solana_client::nonblocking::rpc_client::RpcClient;
+
+use crate::{rpc_pool::RpcPool, Miner};
+
+const RERANK_INTERVAL: Duration = Duration::from_secs(300);
+
+// Endpoints slower than this are ranked as unreachable
+const PING_TIMEOUT: Duration = Duration::from_secs(5);
+
+struct RankedEndpoint {
+    client: Arc<RpcClient>,
+    latency: Option<Duration>,
+}
+
+/// Sends through whichever pool endpoint answered `getSlot` fastest, falling back to the
+/// next fastest on retries.
+pub struct MultiRegionRpcSelector {
+    ranking: RwLock<Vec<RankedEndpoint>>,
+    metrics_file: Option<PathBuf>,
+}
+
+impl MultiRegionRpcSelector {
+    pub fn new(pool: &RpcPool, metrics_file: Option<PathBuf>) -> Self {
+        let ranking = pool
+            .clients()
+            .iter()
+            .map(|client| RankedEndpoint {
+                client: client.clone(),
+                latency: None,
+            })
+            .collect();
+        Self {
+            ranking: RwLock::new(ranking),
+            metrics_file,
+        }
+    }
+
+    /// The endpoint at `rank`, where 0 is the fastest.
+    pub fn get(&self, rank: usize) -> Arc<RpcClient> {
+        let ranking = self.ranking.read().expect("failed to lock mutex");
+        ranking[rank % ranking.len()].client.clone()
+    }
+
+    /// Pings every endpoint and reorders them by round-trip latency, unreachable last.
+    pub async fn rank(&self) {
+        let clients: Vec<Arc<RpcClient>> = self
+            .ranking
+            .read()
+            .expect("failed to lock mutex")
+            .iter()
+            .map(|endpoint| endpoint.client.clone())
+            .collect();
+        let latencies = futures::future::join_all(clients.iter().map(|client| ping(client))).await;
+        let mut ranking: Vec<RankedEndpoint> = clients
+            .into_iter()
+            .zip(latencies)
+            .map(|(client, latency)| RankedEndpoint { client, latency })
+            .collect();
+        ranking.sort_by_key(|endpoint| endpoint.latency.unwrap_or(Duration::MAX));
+        *self.ranking.write().expect("failed to lock mutex") = ranking;
+
+        if let Some(path) = self.metrics_file.as_ref() {
+            if let Err(err) = self.write_metrics(path) {
+                println!("Failed to write RPC latency metrics to {:?}: {}", path, err);
+            }
+        }
+    }
+
+    /// Writes the `rpc_endpoint_latency_ms` gauge in the Prometheus text format, for
+    /// collection by node_exporter's textfile collector.
+    fn write_metrics(&self, path: &Path) -> io::Result<()> {
+        let mut text = String::from(
+            "# HELP rpc_endpoint_latency_ms Round-trip latency of getSlot against an RPC endpoint\n\
+             # TYPE rpc_endpoint_latency_ms gauge\n",
+        );
+        for endpoint in self.ranking.read().expect("failed to lock mutex").iter() {
+            if let Some(latency) = endpoint.latency {
+                writeln!(
+                    text,
+                    "rpc_endpoint_latency_ms{{endpoint=\"{}\"}} {}",
+                    endpoint.client.url(),
+                    latency.as_millis()
+                )
+                .ok();
+            }
+        }
+        // Renamed into place so the collector never reads a partial file
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, text)?;
+        fs::rename(tmp_path, path)
+    }
+}
+
+async fn ping(client: &RpcClient) -> Option<Duration> {
+    let started_at = Instant::now();
+    match tokio::time::timeout(PING_TIMEOUT, client.get_slot()).await {
+        Ok(Ok(_)) => Some(started_at.elapsed()),
+        _ => None,
+    }
+}
+
+impl Miner {
+    /// Ranks the RPC pool by latency now, then again every five minutes in the background.
+    pub async fn spawn_rpc_selector(&self) {
+        let Some(selector) = self.rpc_selector.clone() else {
+            return;
+        };
+        selector.rank().await;
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(RERANK_INTERVAL).await;
+                selector.rank().await;
+            }
+        });
+    }
+
+    /// The endpoint for simulations, blockhash fetches, and confirmation polling: the
+    /// fastest one when `--rpc-select-by-latency` is set.
+    pub fn read_client(&self) -> Arc<RpcClient> {
+        match self.rpc_selector.as_ref() {
+            Some(selector) => selector.get(0),
+            None => self.rpc_client.clone(),
+        }
+    }
+
+    /// The endpoint to use for the `attempt`th send, by latency rank when
+    /// `--rpc-select-by-latency` is set and by pool order otherwise.
+    pub fn gateway(&self, attempt: usize) -> Arc<RpcClient> {
+        match self.rpc_selector.as_ref() {
+            Some(selector) => selector.get(attempt),
+            None => self.rpc_pool.get(attempt),
+        }
+    }
+}
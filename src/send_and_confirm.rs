@@ -1,22 +1,44 @@
 use std::{
-    io::{stdout, Write},
-    time::Duration,
+    sync::{atomic::Ordering, Arc},
+    time::{Duration, Instant},
 };
 
+use clap::ValueEnum;
 use solana_client::{
     client_error::{ClientError, ClientErrorKind, Result as ClientResult},
-    rpc_config::{RpcSendTransactionConfig, RpcSimulateTransactionConfig},
+    rpc_config::RpcSendTransactionConfig,
+};
+use solana_program::{
+    address_lookup_table::AddressLookupTableAccount,
+    hash::Hash,
+    instruction::Instruction,
+    message::{v0::Message as MessageV0, VersionedMessage},
+    pubkey::Pubkey,
 };
-use solana_program::instruction::Instruction;
 use solana_sdk::{
+    clock::Slot,
     commitment_config::CommitmentLevel,
     compute_budget::ComputeBudgetInstruction,
-    signature::{Signature, Signer},
-    transaction::Transaction,
+    signature::{Keypair, Signature, Signer},
+    transaction::{Transaction, TransactionError, VersionedTransaction},
 };
 use solana_transaction_status::{TransactionConfirmationStatus, UiTransactionEncoding};
+use uuid::Uuid;
 
-use crate::Miner;
+use crate::{
+    annotation::{consumed_cus, TransactionAnnotation},
+    auto_batch::{auto_batch, check_fits, BatchOptimizer},
+    compute_budget::{
+        static_compute_unit_limit, transaction_compute_unit_price, InstructionBudgetValidator,
+        MAX_COMPUTE_UNIT_LIMIT,
+    },
+    error::MinerError,
+    lifecycle::{batch_println, TxContext},
+    transaction::{verify_transaction_signature, MinerTransaction},
+    utils::encode_instructions_compact,
+    wallet_mux::{payer_signers, sign_with_cosigners, TransactionSigner},
+    Miner,
+};
 
 const RPC_RETRIES: usize = 0;
 const SIMULATION_RETRIES: usize = 4;
@@ -26,125 +48,798 @@ const CONFIRM_RETRIES: usize = 4;
 const CONFIRM_DELAY: u64 = 5000;
 const GATEWAY_DELAY: u64 = 2000;
 
+// Most unique accounts a transaction can reference
+pub const MAX_TX_ACCOUNTS: usize = 64;
+
+/// Encoding of transactions sent over RPC.
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+pub enum SendEncoding {
+    /// Slow to encode for large transactions, but accepted by older RPC nodes
+    Base58,
+    #[default]
+    Base64,
+}
+
+impl From<SendEncoding> for UiTransactionEncoding {
+    fn from(encoding: SendEncoding) -> Self {
+        match encoding {
+            SendEncoding::Base58 => UiTransactionEncoding::Base58,
+            SendEncoding::Base64 => UiTransactionEncoding::Base64,
+        }
+    }
+}
+
+/// A signature that reached confirmed commitment.
+#[derive(Clone, Copy, Debug)]
+pub struct Confirmation {
+    pub signature: Signature,
+    pub slot: Slot,
+    pub latency: Duration,
+}
+
+/// The signatures of a submitted batch, in order, with an annotation for each.
+#[derive(Debug)]
+pub struct BatchResult {
+    pub group_id: Uuid,
+    pub signatures: Vec<Signature>,
+    pub annotations: Vec<TransactionAnnotation>,
+}
+
+impl BatchResult {
+    pub fn new(group_id: Uuid) -> Self {
+        Self {
+            group_id,
+            signatures: vec![],
+            annotations: vec![],
+        }
+    }
+
+    pub fn push(&mut self, (sig, annotation): (Signature, TransactionAnnotation)) {
+        self.signatures.push(sig);
+        self.annotations.push(annotation);
+    }
+
+    fn log_annotations(&self) {
+        for (sig, annotation) in self.signatures.iter().zip(self.annotations.iter()) {
+            batch_println!("{}: {}", sig, annotation);
+        }
+    }
+}
+
 impl Miner {
+    pub async fn send_and_confirm(
+        &self,
+        ixs: &[Instruction],
+        dynamic_cus: bool,
+        skip_confirm: bool,
+    ) -> ClientResult<Signature> {
+        let mut ixs = ixs.to_vec();
+        if self.instruction_dedup {
+            let removed = BatchOptimizer::dedup_instructions(&mut ixs);
+            if !removed.is_empty() {
+                batch_println!(
+                    "Warning: removed {} duplicate instructions [{}]",
+                    removed.len(),
+                    encode_instructions_compact(&removed)
+                );
+            }
+        }
+        let ixs = ixs.as_slice();
+        let batch = if self.lookup_tables.is_empty() {
+            // Split instructions that do not fit in one transaction, unless --no-auto-batch
+            let payer = self.signer().pubkey();
+            let reserved = self.fee_token_reserve(&payer);
+            let mut txs_ixs = if self.no_auto_batch {
+                check_fits(&[reserved.as_slice(), ixs].concat(), &payer, dynamic_cus)?;
+                vec![ixs.to_vec()]
+            } else {
+                auto_batch(
+                    ixs,
+                    &payer,
+                    dynamic_cus,
+                    self.batch_split_strategy,
+                    &reserved,
+                )?
+            };
+            BatchOptimizer::merge_compute_budget(&mut txs_ixs);
+            self.send_and_confirm_batch(txs_ixs, &[], dynamic_cus, skip_confirm)
+                .await?
+        } else {
+            let lookup_tables = self.load_lookup_tables().await?;
+            self.send_versioned_and_confirm_batch(
+                vec![ixs.to_vec()],
+                lookup_tables,
+                dynamic_cus,
+                skip_confirm,
+            )
+            .await?
+        };
+        // The last transaction of a split batch is the one that completes it
+        Ok(*batch.signatures.last().expect("batch is never empty"))
+    }
+
     pub async fn send_and_confirm_batch(
         &self,
         txs_ixs: Vec<Vec<Instruction>>,
+        cosigners: &[Arc<Keypair>],
         dynamic_cus: bool,
         skip_confirm: bool,
-    ) -> ClientResult<Vec<Signature>> {
-        let mut stdout = stdout();
+    ) -> ClientResult<BatchResult> {
+        self.check_queue_depth(txs_ixs.len())?;
         let signer = self.signer();
-        let client = self.rpc_client.clone();
-        let mut signatures = Vec::new();
+        let context = self.batch_context();
+        context
+            .scope(async {
+                let mut batch = BatchResult::new(context.group_id);
 
-        for ixs in txs_ixs.iter() {
-            let balance = client.get_balance(&signer.pubkey()).await?;
-            if balance <= 0 {
-                return Err(ClientError {
-                    request: None,
-                    kind: ClientErrorKind::Custom("Insufficient SOL balance".into()),
-                });
-            }
+                self.warm_account_cache(signer.pubkey(), &txs_ixs).await?;
+                for (index, ixs) in txs_ixs.iter().enumerate() {
+                    let ctx = context.tx(index);
+                    let (tx, last_valid_block_height, simulation_cus) = self
+                        .prepare_transaction(&signer, cosigners, ixs, dynamic_cus, ctx)
+                        .await?;
+                    self.stagger_send(index).await;
+                    batch.push(
+                        self.send_signed(
+                            &tx,
+                            last_valid_block_height,
+                            simulation_cus,
+                            skip_confirm,
+                            ctx,
+                        )
+                        .await?,
+                    );
+                }
 
-            let (mut hash, mut slot) = client
-                .get_latest_blockhash_with_commitment(self.rpc_client.commitment())
-                .await?;
+                self.finish_batch(&batch, skip_confirm).await?;
+                Ok::<_, ClientError>(batch)
+            })
+            .await
+    }
 
-            let mut tx = Transaction::new_with_payer(ixs, Some(&signer.pubkey()));
+    /// Like `send_and_confirm_batch`, but builds v0 transactions that can resolve accounts
+    /// through the given address lookup tables.
+    pub async fn send_versioned_and_confirm_batch(
+        &self,
+        txs_ixs: Vec<Vec<Instruction>>,
+        lookup_tables: Vec<AddressLookupTableAccount>,
+        dynamic_cus: bool,
+        skip_confirm: bool,
+    ) -> ClientResult<BatchResult> {
+        self.check_queue_depth(txs_ixs.len())?;
+        let signer = self.signer();
+        let context = self.batch_context();
+        context
+            .scope(async {
+                let mut batch = BatchResult::new(context.group_id);
 
-            if dynamic_cus {
-                let mut sim_attempts = 0;
-                'simulate: loop {
-                    let sim_res = client
-                        .simulate_transaction_with_config(
+                self.warm_account_cache(signer.pubkey(), &txs_ixs).await?;
+                for (index, ixs) in txs_ixs.iter().enumerate() {
+                    let ctx = context.tx(index);
+                    self.refresh_account_cache(signer.pubkey(), ixs).await?;
+                    ctx.event("balance check");
+                    let (payer, _) = self.select_fee_payer(&signer, ixs)?;
+                    ctx.event("blockhash fetch");
+                    let blockhash = self.latest_blockhash().await?;
+                    // Versioned transactions are signed as they are built
+                    ctx.event(if dynamic_cus {
+                        "simulation start"
+                    } else {
+                        "transaction sign"
+                    });
+                    let (tx, simulation_cus) = self
+                        .build_versioned_transaction(
+                            &signer,
+                            payer,
+                            ixs,
+                            &lookup_tables,
+                            blockhash.hash,
+                            dynamic_cus,
+                        )
+                        .await?;
+                    if dynamic_cus {
+                        ctx.event("simulation end");
+                    }
+                    self.log_blockhash(&blockhash);
+                    self.stagger_send(index).await;
+                    batch.push(
+                        self.send_signed(
                             &tx,
-                            RpcSimulateTransactionConfig {
-                                sig_verify: false,
-                                replace_recent_blockhash: true,
-                                commitment: Some(self.rpc_client.commitment()),
-                                encoding: Some(UiTransactionEncoding::Base64),
-                                accounts: None,
-                                min_context_slot: None,
-                                inner_instructions: false,
-                            },
+                            blockhash.last_valid_block_height,
+                            simulation_cus,
+                            skip_confirm,
+                            ctx,
                         )
-                        .await;
-                    match sim_res {
-                        Ok(sim_res) => {
-                            if let Some(err) = sim_res.value.err {
-                                println!("Simulation error: {:?}", err);
-                                sim_attempts += 1;
-                                if sim_attempts > SIMULATION_RETRIES {
-                                    return Err(ClientError {
-                                        request: None,
-                                        kind: ClientErrorKind::Custom("Simulation failed".into()),
-                                    });
-                                }
-                            } else if let Some(units_consumed) = sim_res.value.units_consumed {
-                                println!("Dynamic CUs: {:?}", units_consumed);
-                                let cu_budget_ix = ComputeBudgetInstruction::set_compute_unit_limit(
-                                    units_consumed as u32 + 1000,
-                                );
-                                let cu_price_ix =
-                                    ComputeBudgetInstruction::set_compute_unit_price(self.priority_fee);
-                                let mut final_ixs = vec![cu_budget_ix, cu_price_ix];
-                                final_ixs.extend_from_slice(ixs);
-                                tx = Transaction::new_with_payer(&final_ixs, Some(&signer.pubkey()));
-                                break 'simulate;
-                            }
+                        .await?,
+                    );
+                }
+
+                self.finish_batch(&batch, skip_confirm).await?;
+                Ok::<_, ClientError>(batch)
+            })
+            .await
+    }
+
+    /// Simulates, budgets, and signs each transaction up front so it can be submitted
+    /// later without paying for simulation or signing on the hot path. Each transaction
+    /// is returned alongside the last block height its blockhash is valid for.
+    pub async fn precompute_transactions(
+        &self,
+        txs_ixs: Vec<Vec<Instruction>>,
+        dynamic_cus: bool,
+    ) -> ClientResult<Vec<(Transaction, u64)>> {
+        self.check_queue_depth(txs_ixs.len())?;
+        let signer = self.signer();
+        let context = self.batch_context();
+        context
+            .scope(async {
+                let mut txs = Vec::new();
+
+                self.warm_account_cache(signer.pubkey(), &txs_ixs).await?;
+                for (index, ixs) in txs_ixs.iter().enumerate() {
+                    let (tx, last_valid_block_height, _) = self
+                        .prepare_transaction(&signer, &[], ixs, dynamic_cus, context.tx(index))
+                        .await?;
+                    txs.push((tx, last_valid_block_height));
+                }
+
+                Ok::<_, ClientError>(txs)
+            })
+            .await
+    }
+
+    /// Submits transactions produced by `precompute_transactions`, failing fast on any
+    /// transaction whose blockhash has already expired.
+    pub async fn submit_precomputed(
+        &self,
+        txs: Vec<(Transaction, u64)>,
+        skip_confirm: bool,
+    ) -> ClientResult<BatchResult> {
+        let client = self.rpc_client.clone();
+        let context = self.batch_context();
+        context
+            .scope(async {
+                let mut batch = BatchResult::new(context.group_id);
+
+                for (index, (tx, last_valid_block_height)) in txs.iter().enumerate() {
+                    let ctx = context.tx(index);
+                    let block_height = self.guarded_rpc(client.get_block_height()).await?;
+                    if block_height > *last_valid_block_height {
+                        return Err(ClientError {
+                            request: None,
+                            kind: ClientErrorKind::Custom("Precomputed transaction expired".into()),
+                        });
+                    }
+                    self.stagger_send(index).await;
+                    batch.push(
+                        self.send_signed(tx, *last_valid_block_height, None, skip_confirm, ctx)
+                            .await?,
+                    );
+                }
+
+                self.finish_batch(&batch, skip_confirm).await?;
+                Ok::<_, ClientError>(batch)
+            })
+            .await
+    }
+
+    /// Builds, budgets, and signs the legacy transaction for one entry of a batch, along
+    /// with any of `cosigners` its instructions need. Returns it with the last block height
+    /// its blockhash is valid for and its simulated compute units.
+    async fn prepare_transaction(
+        &self,
+        signer: &Keypair,
+        cosigners: &[Arc<Keypair>],
+        ixs: &[Instruction],
+        dynamic_cus: bool,
+        ctx: TxContext<'_>,
+    ) -> ClientResult<(Transaction, u64, Option<u64>)> {
+        self.refresh_account_cache(signer.pubkey(), ixs).await?;
+        ctx.event("balance check");
+        let (payer, payer_pubkey) = self.select_fee_payer(signer, ixs)?;
+        ctx.event("blockhash fetch");
+        let blockhash = self.latest_blockhash().await?;
+
+        if dynamic_cus {
+            ctx.event("simulation start");
+        }
+        let (mut tx, simulation_cus) = match self
+            .build_transaction(&payer_pubkey, ixs, dynamic_cus)
+            .await
+        {
+            Ok(built) => {
+                if self.fallback_ixs.is_some() {
+                    batch_println!(
+                        "Using primary instruction set [{}]",
+                        encode_instructions_compact(ixs)
+                    );
+                }
+                built
+            }
+            Err(err) => match self.fallback_ixs.as_ref() {
+                Some(fallback_ixs) if dynamic_cus => {
+                    batch_println!(
+                        "Primary instruction set failed ({}), using fallback [{}]",
+                        err,
+                        encode_instructions_compact(fallback_ixs)
+                    );
+                    self.build_transaction(&payer_pubkey, fallback_ixs, dynamic_cus)
+                        .await?
+                }
+                _ => return Err(err),
+            },
+        };
+        if dynamic_cus {
+            ctx.event("simulation end");
+        }
+        self.log_blockhash(&blockhash);
+        ctx.event("transaction sign");
+        sign_with_cosigners(&mut tx, payer, signer, cosigners, blockhash.hash)?;
+        Ok((tx, blockhash.last_valid_block_height, simulation_cus))
+    }
+
+    /// Waits for a sent batch to finalize, unless its confirmation was skipped, and logs
+    /// its annotations.
+    pub async fn finish_batch(&self, batch: &BatchResult, skip_confirm: bool) -> ClientResult<()> {
+        if !skip_confirm {
+            self.wait_for_batch_finalized(&batch.signatures).await?;
+        }
+        if self.log_tx_annotations {
+            batch.log_annotations();
+        }
+        self.rotate_fee_payer_if_due().await;
+        Ok(())
+    }
+
+    /// Picks the fee payer for a transaction built from `ixs` and checks it can pay.
+    pub fn select_fee_payer(
+        &self,
+        signer: &Keypair,
+        ixs: &[Instruction],
+    ) -> ClientResult<(Option<Arc<dyn TransactionSigner>>, Pubkey)> {
+        let payer = self.fee_payer(ixs);
+        let payer_pubkey = payer
+            .as_ref()
+            .map_or(signer.pubkey(), |payer| payer.pubkey());
+        self.check_fee_payer_balance(&payer_pubkey)?;
+        Ok((payer, payer_pubkey))
+    }
+
+    pub fn check_fee_payer_balance(&self, payer: &Pubkey) -> ClientResult<()> {
+        let balance = self
+            .account_cache
+            .get(payer)
+            .map_or(0, |account| account.lamports);
+        self.observe_balance(payer, balance);
+        if balance == 0 {
+            return Err(ClientError {
+                request: None,
+                kind: ClientErrorKind::Custom("Insufficient SOL balance".into()),
+            });
+        }
+        Ok(())
+    }
+
+    /// Waits `--stagger-batch-sends` before every send in a batch but the first.
+    async fn stagger_send(&self, index: usize) {
+        if let Some(delay) = self.stagger_batch_sends.filter(|_| index > 0) {
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    pub fn check_queue_depth(&self, submitted: usize) -> Result<(), MinerError> {
+        match self.max_queue_depth {
+            Some(capacity) if submitted > capacity => Err(MinerError::QueueFull {
+                capacity,
+                submitted,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Rejects transactions referencing more unique accounts, including the fee payer and
+    /// program ids, than `--tx-account-count-limit` allows.
+    pub fn check_account_count(&self, tx: &impl MinerTransaction) -> Result<(), MinerError> {
+        let count = tx.account_count();
+        if count > self.tx_account_count_limit {
+            return Err(MinerError::TooManyAccounts {
+                count,
+                max: self.tx_account_count_limit,
+            });
+        }
+        Ok(())
+    }
+
+    /// Rejects instructions that would fail on chain, as a transaction is built from them.
+    pub fn validate_instructions(&self, ixs: &[Instruction]) -> ClientResult<()> {
+        InstructionBudgetValidator::validate(ixs)?;
+        if let Some(validator) = self.instruction_validator.as_ref() {
+            validator.validate(ixs)?;
+        }
+        Ok(())
+    }
+
+    pub async fn build_transaction(
+        &self,
+        payer: &Pubkey,
+        ixs: &[Instruction],
+        dynamic_cus: bool,
+    ) -> ClientResult<(Transaction, Option<u64>)> {
+        let unbudgeted_ixs = self.with_fee_token_payment(payer, ixs, false);
+        let tx = Transaction::new_with_payer(&unbudgeted_ixs, Some(payer));
+        if !dynamic_cus {
+            self.validate_instructions(&unbudgeted_ixs)?;
+            return Ok((tx, None));
+        }
+
+        let units_consumed = self.simulate_compute_units(&tx, ixs).await?;
+        let final_ixs = self.budget_instructions(ixs, units_consumed);
+        let final_ixs = self.with_fee_token_payment(payer, &final_ixs, true);
+        self.validate_instructions(&final_ixs)?;
+        let tx = Transaction::new_with_payer(&final_ixs, Some(payer));
+        Ok((tx, Some(units_consumed)))
+    }
+
+    async fn build_versioned_transaction(
+        &self,
+        signer: &Keypair,
+        payer: Option<Arc<dyn TransactionSigner>>,
+        ixs: &[Instruction],
+        lookup_tables: &[AddressLookupTableAccount],
+        blockhash: Hash,
+        dynamic_cus: bool,
+    ) -> ClientResult<(VersionedTransaction, Option<u64>)> {
+        let payer_pubkey = payer
+            .as_ref()
+            .map_or(signer.pubkey(), |payer| payer.pubkey());
+        let compile = |ixs: &[Instruction], simulated: bool| {
+            let ixs = self.with_fee_token_payment(&payer_pubkey, ixs, simulated);
+            self.validate_instructions(&ixs)?;
+            MessageV0::try_compile(&payer_pubkey, &ixs, lookup_tables, blockhash)
+                .map(VersionedMessage::V0)
+                .map_err(|err| ClientError {
+                    request: None,
+                    kind: ClientErrorKind::Custom(format!(
+                        "Failed to compile transaction: {}",
+                        err
+                    )),
+                })
+        };
+
+        let (message, simulation_cus) = if dynamic_cus {
+            // Simulation skips signature verification, so an unsigned copy is enough
+            let message = compile(ixs, false)?;
+            let unsigned = VersionedTransaction {
+                signatures: vec![
+                    Signature::default();
+                    message.header().num_required_signatures as usize
+                ],
+                message,
+            };
+            let units_consumed = self.simulate_compute_units(&unsigned, ixs).await?;
+            let final_ixs = self.budget_instructions(ixs, units_consumed);
+            (compile(&final_ixs, true)?, Some(units_consumed))
+        } else {
+            (compile(ixs, false)?, None)
+        };
+        let required =
+            &message.static_account_keys()[..message.header().num_required_signatures as usize];
+        let signers = payer_signers(payer, signer, required);
+        let tx = VersionedTransaction::try_new(message, &signers).map_err(|err| ClientError {
+            request: None,
+            kind: ClientErrorKind::Custom(format!("Failed to sign transaction: {}", err)),
+        })?;
+        Ok((tx, simulation_cus))
+    }
+
+    /// Simulates `tx`, built from `ixs`, until it reports the compute units it consumes.
+    pub async fn simulate_compute_units(
+        &self,
+        tx: &impl MinerTransaction,
+        ixs: &[Instruction],
+    ) -> ClientResult<u64> {
+        if self.simulate_account_owner_check {
+            self.check_account_owners(ixs).await?;
+        }
+        if self.simulate_min_accounts_for_rent {
+            self.check_rent_exemption(ixs).await?;
+        }
+        let max_retries = self.simulate_max_retries.unwrap_or(SIMULATION_RETRIES);
+        let mut sim_attempts = 0;
+        loop {
+            let Ok(sim_res) =
+                tokio::time::timeout(self.simulate_timeout, self.simulate_transaction(tx)).await
+            else {
+                if self.simulate_fail_fast {
+                    batch_println!(
+                        "Simulation timed out after {}s",
+                        self.simulate_timeout.as_secs()
+                    );
+                    return Err(MinerError::SimulationFailed.into());
+                }
+                sim_attempts += 1;
+                if sim_attempts > max_retries {
+                    // Sending with the runtime's default limit beats not sending at all
+                    let units = static_compute_unit_limit(ixs) as u64;
+                    batch_println!("Simulation timed out, using static CUs: {}", units);
+                    return Ok(units);
+                }
+                batch_println!(
+                    "Simulation timed out after {}s",
+                    self.simulate_timeout.as_secs()
+                );
+                continue;
+            };
+            match sim_res {
+                Ok(sim_res) => {
+                    if let Some(err) = sim_res.value.err {
+                        let decoded = self
+                            .error_decoder
+                            .as_ref()
+                            .and_then(|decoder| decoder.decode(&err));
+                        match decoded.as_ref() {
+                            Some(decoded) => batch_println!(
+                                "Simulation error: {} [{}]",
+                                decoded,
+                                encode_instructions_compact(ixs)
+                            ),
+                            None => batch_println!(
+                                "Simulation error: {:?} [{}]",
+                                err,
+                                encode_instructions_compact(ixs)
+                            ),
                         }
-                        Err(err) => {
-                            println!("Simulation error: {:?}", err);
-                            sim_attempts += 1;
-                            if sim_attempts > SIMULATION_RETRIES {
-                                return Err(ClientError {
-                                    request: None,
-                                    kind: ClientErrorKind::Custom("Simulation failed".into()),
-                                });
+                        sim_attempts += 1;
+                        if self.simulate_fail_fast || sim_attempts > max_retries {
+                            return Err(decoded.unwrap_or(MinerError::SimulationFailed).into());
+                        }
+                    } else if let Some(units_consumed) = sim_res.value.units_consumed {
+                        batch_println!("Dynamic CUs: {:?}", units_consumed);
+                        if let Some(slots) = self.simulate_with_slots {
+                            match self.check_simulation_consistency(tx, slots).await {
+                                Ok(report) if report.consistent => {
+                                    batch_println!("Simulation consistent over {} slots", slots + 1)
+                                }
+                                Ok(report) => batch_println!(
+                                    "Warning: simulation over {} slots varied in {}",
+                                    slots + 1,
+                                    report.varying_fields.join(", ")
+                                ),
+                                Err(err) => {
+                                    batch_println!(
+                                        "Failed to check simulation consistency: {}",
+                                        err
+                                    )
+                                }
                             }
                         }
+                        return Ok(units_consumed);
+                    }
+                }
+                Err(err) => {
+                    batch_println!(
+                        "Simulation error: {:?} [{}]",
+                        err,
+                        encode_instructions_compact(ixs)
+                    );
+                    sim_attempts += 1;
+                    if self.simulate_fail_fast || sim_attempts > max_retries {
+                        return Err(MinerError::SimulationFailed.into());
                     }
                 }
             }
+        }
+    }
 
-            tx.sign(&[&signer], hash);
-            let send_cfg = RpcSendTransactionConfig {
-                skip_preflight: true,
-                preflight_commitment: Some(CommitmentLevel::Finalized),
-                encoding: Some(UiTransactionEncoding::Base64),
-                max_retries: Some(RPC_RETRIES),
-                min_context_slot: Some(slot),
-            };
+    /// Prepends a compute unit limit sized from simulation and the priority fee to `ixs`.
+    pub fn budget_instructions(
+        &self,
+        ixs: &[Instruction],
+        units_consumed: u64,
+    ) -> Vec<Instruction> {
+        let units = (units_consumed as f64 * self.cu_tuner.factor()).ceil() as u32;
+        let cu_budget_ix = ComputeBudgetInstruction::set_compute_unit_limit(
+            units.saturating_add(1000).min(MAX_COMPUTE_UNIT_LIMIT),
+        );
+        let cu_price_ix =
+            ComputeBudgetInstruction::set_compute_unit_price(self.scheduled_priority_fee());
+        let mut final_ixs = vec![cu_budget_ix, cu_price_ix];
+        final_ixs.extend_from_slice(ixs);
+        final_ixs
+    }
+
+    pub fn send_config(&self, slot: Slot) -> RpcSendTransactionConfig {
+        RpcSendTransactionConfig {
+            skip_preflight: true,
+            preflight_commitment: Some(CommitmentLevel::Finalized),
+            encoding: Some(self.send_encoding.into()),
+            max_retries: Some(RPC_RETRIES),
+            min_context_slot: Some(slot),
+        }
+    }
 
-            let mut attempts = 0;
-            loop {
-                match client.send_transaction_with_config(&tx, send_cfg.clone()).await {
-                    Ok(sig) => {
-                        signatures.push(sig);
-                        if skip_confirm {
-                            break;
+    pub async fn send_signed(
+        &self,
+        tx: &impl MinerTransaction,
+        last_valid_block_height: u64,
+        simulation_cus: Option<u64>,
+        skip_confirm: bool,
+        ctx: TxContext<'_>,
+    ) -> ClientResult<(Signature, TransactionAnnotation)> {
+        self.check_account_count(tx)?;
+        verify_transaction_signature(tx)?;
+        let send_cfg = self.send_config(last_valid_block_height);
+        let fee = transaction_compute_unit_price(tx);
+        let mut annotation = TransactionAnnotation::new(tx, simulation_cus);
+
+        let attempts_per_rpc = self
+            .send_max_retries_per_rpc
+            .unwrap_or(GATEWAY_RETRIES + 1)
+            .max(1);
+        let max_attempts = attempts_per_rpc * self.rpc_pool.len();
+        let mut attempts = 0;
+        loop {
+            if let Some(limit) = self.pending_signatures.as_ref() {
+                limit.wait_for_capacity(&self.rpc_client).await;
+            }
+            if let Some(throttle) = self.throttle.as_ref() {
+                throttle.acquire().await;
+            }
+            // Paced last, so nothing else delays the send past the slot boundary
+            self.pace_submission().await;
+            let gateway = self.gateway(attempts / attempts_per_rpc);
+            ctx.event("send attempt");
+            let sent = match self.tpu_sender.as_ref() {
+                Some(tpu) => tpu.send(tx).await,
+                None => {
+                    self.guarded_rpc(gateway.send_transaction_with_config(tx, send_cfg))
+                        .await
+                }
+            };
+            match sent {
+                Ok(sig) => {
+                    let sent_at = Instant::now();
+                    self.watch_propagation(sig, sent_at);
+                    if let Some(rebroadcaster) = self.rebroadcaster.as_ref() {
+                        rebroadcaster.track(sig, tx, last_valid_block_height, send_cfg);
+                    }
+                    self.metrics
+                        .transactions_sent
+                        .fetch_add(1, Ordering::Relaxed);
+                    if let Some(limit) = self.pending_signatures.as_ref() {
+                        limit.track(sig, last_valid_block_height);
+                    }
+                    if skip_confirm {
+                        return Ok((sig, annotation));
+                    }
+                    batch_println!(
+                        "Transaction submitted with signature: {}",
+                        self.signature_display.format(&sig)
+                    );
+                    ctx.event("confirmation poll");
+                    let confirmed = self.confirm_any(&[sig], sent_at).await;
+                    let confirmation = confirmed.as_ref().ok().and_then(Option::as_ref);
+                    if let Some(slot) = self.fee_sample_slot(confirmation).await {
+                        self.fee_history.record(slot, fee, confirmation.is_some());
+                    }
+                    if let Some(confirmation) = confirmed? {
+                        ctx.event("confirmation received");
+                        if let Some(limit) = self.pending_signatures.as_ref() {
+                            limit.resolve(&sig);
                         }
-                        // Confirm transaction logic here
-                        // This is simplified; you'll need to implement actual confirmation logic
-                        println!("Transaction submitted with signature: {:?}", sig);
-                        break;
+                        if let Some(scheduler) = self.fee_decay.as_ref() {
+                            scheduler.lower();
+                        }
+                        annotation.confirmation_slot = Some(confirmation.slot);
+                        annotation.confirmation_latency_ms =
+                            Some(confirmation.latency.as_millis() as u64);
+                        if self.log_tx_annotations || self.track_fee_refunds {
+                            if let Ok(Some(meta)) = self.fetch_transaction_meta(&sig).await {
+                                annotation.actual_cus = consumed_cus(&meta);
+                                self.collect_fee_refund(&sig, annotation.fee_lamports, meta.fee);
+                            }
+                        }
+                        return Ok((sig, annotation));
+                    }
+                    batch_println!("Transaction did not land");
+                }
+                Err(err) => {
+                    batch_println!("Error submitting transaction: {:?}", err);
+                    if self.circuit_open() {
+                        return Err(MinerError::CircuitBreakerOpen.into());
                     }
-                    Err(err) => {
-                        println!("Error submitting transaction: {:?}", err);
-                        attempts += 1;
-                        if attempts > GATEWAY_RETRIES {
-                            return Err(ClientError {
-                                request: None,
-                                kind: ClientErrorKind::Custom("Max retries exceeded".into()),
-                            });
+                }
+            }
+            attempts += 1;
+            if attempts >= max_attempts {
+                return Err(ClientError {
+                    request: None,
+                    kind: ClientErrorKind::Custom("Max retries exceeded".into()),
+                });
+            }
+            if let Some(budget) = ctx.batch.retry_budget.as_ref() {
+                budget.consume()?;
+            }
+            if let Some(scheduler) = self.fee_decay.as_ref() {
+                scheduler.raise();
+            }
+            std::thread::sleep(Duration::from_millis(GATEWAY_DELAY));
+        }
+    }
+
+    /// Polls the given signatures and returns the first one to reach confirmed commitment.
+    /// Fails with `MinerError::ConfirmationTimeout` if `--confirm-timeout` elapses first.
+    pub async fn confirm_any(
+        &self,
+        sigs: &[Signature],
+        sent_at: Instant,
+    ) -> ClientResult<Option<Confirmation>> {
+        let Some(timeout) = self.confirm_timeout else {
+            return Ok(self.await_confirmation(sigs, sent_at).await);
+        };
+        tokio::time::timeout(timeout, self.await_confirmation(sigs, sent_at))
+            .await
+            .map_err(|_| MinerError::ConfirmationTimeout(timeout).into())
+    }
+
+    pub async fn poll_confirmations(
+        &self,
+        sigs: &[Signature],
+        sent_at: Instant,
+    ) -> Option<Confirmation> {
+        let client = self.read_client();
+        for _ in 0..CONFIRM_RETRIES {
+            tokio::time::sleep(Duration::from_millis(CONFIRM_DELAY)).await;
+            match self.guarded_rpc(client.get_signature_statuses(sigs)).await {
+                Ok(signature_statuses) => {
+                    for (sig, signature_status) in sigs.iter().zip(signature_statuses.value) {
+                        let Some(signature_status) = signature_status else {
+                            continue;
+                        };
+                        match signature_status.confirmation_status {
+                            Some(TransactionConfirmationStatus::Confirmed)
+                            | Some(TransactionConfirmationStatus::Finalized) => {
+                                batch_println!("Transaction landed!");
+                                let confirmed_at = Instant::now();
+                                self.record_confirmation(
+                                    signature_status.slot,
+                                    sent_at,
+                                    confirmed_at,
+                                    signature_status.err.as_ref(),
+                                );
+                                return Some(Confirmation {
+                                    signature: *sig,
+                                    slot: signature_status.slot,
+                                    latency: confirmed_at.duration_since(sent_at),
+                                });
+                            }
+                            Some(TransactionConfirmationStatus::Processed) => {}
+                            None => batch_println!("No status"),
                         }
-                        std::thread::sleep(Duration::from_millis(GATEWAY_DELAY));
                     }
                 }
+                Err(err) => {
+                    batch_println!("Error confirming transaction: {:?}", err);
+                }
             }
         }
+        None
+    }
 
-        Ok(signatures)
+    pub fn record_confirmation(
+        &self,
+        slot: Slot,
+        sent_at: Instant,
+        confirmed_at: Instant,
+        err: Option<&TransactionError>,
+    ) {
+        self.confirmation_times.record(slot, sent_at, confirmed_at);
+        self.cu_tuner.record(err);
+        let confirmed = self
+            .metrics
+            .transactions_confirmed
+            .fetch_add(1, Ordering::Relaxed)
+            + 1;
+        self.checkpoint_if_due(confirmed);
     }
 }
@@ -0,0 +1,377 @@
+use std::{
+    fs::OpenOptions,
+    io::{self, Write},
+    path::Path,
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use clap::ValueEnum;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use solana_client::{
+    client_error::{ClientError, ClientErrorKind, Result as ClientResult},
+    rpc_config::RpcSimulateTransactionConfig,
+    rpc_request::RpcRequest,
+    rpc_response::{Response, RpcSimulateTransactionResult},
+};
+use solana_program::{bpf_loader, hash::hash, pubkey::Pubkey, system_instruction, system_program};
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    signature::{Keypair, Signature, Signer},
+    transaction::Transaction,
+};
+use solana_transaction_status::UiTransactionEncoding;
+
+use crate::{
+    account_diff::simulation_accounts_config,
+    lifecycle::{batch_println, TransactionGrouper},
+    transaction::MinerTransaction,
+    Miner,
+};
+
+// Lamports given to overridden program accounts so they look rent exempt
+const PROGRAM_OVERRIDE_LAMPORTS: u64 = 1_000_000_000;
+
+// How often to check for the next slot when simulating across slots
+const SLOT_POLL_DELAY: u64 = 100;
+
+#[derive(Clone, Debug)]
+pub struct ProgramOverride {
+    pub program_id: Pubkey,
+    pub elf: Vec<u8>,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum SimulateCommitment {
+    /// Freshest state and lowest latency, but may not survive a fork
+    Processed,
+    /// Voted on by a supermajority of the cluster
+    Confirmed,
+    /// Rooted state that will not be rolled back
+    Finalized,
+}
+
+impl SimulateCommitment {
+    pub fn config(self) -> CommitmentConfig {
+        match self {
+            Self::Processed => CommitmentConfig::processed(),
+            Self::Confirmed => CommitmentConfig::confirmed(),
+            Self::Finalized => CommitmentConfig::finalized(),
+        }
+    }
+}
+
+/// One line of the `--simulate-log-to-file` log. Transactions are usually simulated
+/// before they are signed, so the message hash identifies them and the signature is null
+/// until signing.
+#[derive(Serialize)]
+struct SimulationLogEntry {
+    signature: Option<String>,
+    message_hash: String,
+    group_id: Option<String>,
+    timestamp: u64,
+    logs: Vec<String>,
+    units_consumed: Option<u64>,
+    error: Option<String>,
+}
+
+impl SimulationLogEntry {
+    fn new(
+        tx: &impl MinerTransaction,
+        res: &ClientResult<Response<RpcSimulateTransactionResult>>,
+    ) -> Self {
+        let (logs, units_consumed, error) = match res {
+            Ok(res) => (
+                res.value.logs.clone().unwrap_or_default(),
+                res.value.units_consumed,
+                res.value.err.as_ref().map(|err| err.to_string()),
+            ),
+            Err(err) => (vec![], None, Some(err.to_string())),
+        };
+        Self {
+            signature: Some(tx.get_signature())
+                .filter(|sig| **sig != Signature::default())
+                .map(|sig| sig.to_string()),
+            message_hash: hash(&tx.message_data()).to_string(),
+            group_id: TransactionGrouper::current().map(|group_id| group_id.to_string()),
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            logs,
+            units_consumed,
+            error,
+        }
+    }
+
+    fn append_to(&self, path: &Path) -> io::Result<()> {
+        let mut line = serde_json::to_vec(self)?;
+        line.push(b'\n');
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?
+            .write_all(&line)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct BalanceOverride {
+    pub pubkey: Pubkey,
+    pub lamports: u64,
+}
+
+/// Parses a `<PUBKEY>=<LAMPORTS>` argument.
+pub fn parse_balance_override(arg: &str) -> Result<BalanceOverride, String> {
+    let (pubkey_arg, lamports) = arg
+        .split_once('=')
+        .ok_or_else(|| format!("expected <PUBKEY>=<LAMPORTS>, got `{}`", arg))?;
+    let pubkey = Pubkey::from_str(pubkey_arg)
+        .map_err(|err| format!("invalid pubkey `{}`: {}", pubkey_arg, err))?;
+    let lamports = lamports
+        .parse()
+        .map_err(|err| format!("invalid lamports `{}`: {}", lamports, err))?;
+    Ok(BalanceOverride { pubkey, lamports })
+}
+
+/// Parses a `<PUBKEY>=<PATH>` argument and loads the program binary at `PATH`.
+pub fn parse_program_override(arg: &str) -> Result<ProgramOverride, String> {
+    let (pubkey, path) = arg
+        .split_once('=')
+        .ok_or_else(|| format!("expected <PUBKEY>=<PATH>, got `{}`", arg))?;
+    let program_id = Pubkey::from_str(pubkey)
+        .map_err(|err| format!("invalid program id `{}`: {}", pubkey, err))?;
+    let elf = std::fs::read(path).map_err(|err| format!("failed to read `{}`: {}", path, err))?;
+    Ok(ProgramOverride { program_id, elf })
+}
+
+/// Prints the program derived addresses logged as `Program log: PDA: [<seed_hash>] <pubkey>`.
+fn print_program_addresses(logs: &[String]) {
+    let mut addresses: Vec<(String, Pubkey)> = logs
+        .iter()
+        .filter_map(|log| log.strip_prefix("Program log: PDA: "))
+        .filter_map(|entry| {
+            let mut tokens = entry.split_whitespace().rev();
+            let pubkey = Pubkey::from_str(tokens.next()?).ok()?;
+            let seed_hash = tokens.next().unwrap_or("-").to_string();
+            Some((seed_hash, pubkey))
+        })
+        .collect();
+    if addresses.is_empty() {
+        return;
+    }
+    addresses.sort();
+    addresses.dedup();
+    let width = addresses
+        .iter()
+        .map(|(seed_hash, _)| seed_hash.len())
+        .max()
+        .unwrap_or_default()
+        .max("SEED HASH".len());
+    println!("{:<width$}  PDA", "SEED HASH", width = width);
+    for (seed_hash, pubkey) in addresses {
+        println!("{:<width$}  {}", seed_hash, pubkey, width = width);
+    }
+}
+
+impl Miner {
+    pub async fn simulate_transaction(
+        &self,
+        tx: &impl MinerTransaction,
+    ) -> ClientResult<Response<RpcSimulateTransactionResult>> {
+        // Read before simulating, so the diff starts from the state the simulation saw
+        let fresh_accounts = if self.simulate_disable_caching && self.simulate_log_accounts_diff {
+            Some(
+                self.guarded_rpc(
+                    self.read_client()
+                        .get_multiple_accounts(tx.static_account_keys()),
+                )
+                .await?,
+            )
+        } else {
+            None
+        };
+        let res = self.request_simulation(tx).await;
+        if let Some(path) = self.simulate_log_file.as_ref() {
+            if let Err(err) = SimulationLogEntry::new(tx, &res).append_to(path) {
+                batch_println!("Failed to write simulation log to {:?}: {}", path, err);
+            }
+        }
+        let res = res?;
+        if self.simulate_log_program_addresses {
+            if let Some(logs) = res.value.logs.as_ref() {
+                print_program_addresses(logs);
+            }
+        }
+        if self.simulate_log_accounts_diff {
+            self.print_accounts_diff(tx, &res.value, fresh_accounts.as_deref());
+        }
+        Ok(res)
+    }
+
+    async fn request_simulation(
+        &self,
+        tx: &impl MinerTransaction,
+    ) -> ClientResult<Response<RpcSimulateTransactionResult>> {
+        let client = self.read_client();
+        let config = RpcSimulateTransactionConfig {
+            sig_verify: false,
+            replace_recent_blockhash: true,
+            commitment: Some(
+                self.simulate_commitment
+                    .unwrap_or_else(|| client.commitment()),
+            ),
+            encoding: Some(UiTransactionEncoding::Base64),
+            accounts: self
+                .simulate_log_accounts_diff
+                .then(|| simulation_accounts_config(tx)),
+            min_context_slot: None,
+            inner_instructions: false,
+        };
+        if self.simulate_program_overrides.is_empty() && self.simulate_balance_overrides.is_empty()
+        {
+            return self
+                .guarded_rpc(client.simulate_transaction_with_config(tx, config))
+                .await;
+        }
+
+        // The typed client has no notion of account overrides, so build the request by hand.
+        // RPC nodes that do not support overrides ignore the extra field, which main rules
+        // out at startup with `supports_account_overrides`.
+        let mut overrides = Map::new();
+        for program in self.simulate_program_overrides.iter() {
+            overrides.insert(
+                program.program_id.to_string(),
+                json!({
+                    "lamports": PROGRAM_OVERRIDE_LAMPORTS,
+                    "data": [BASE64.encode(&program.elf), "base64"],
+                    "owner": bpf_loader::id().to_string(),
+                    "executable": true,
+                    "rentEpoch": u64::MAX,
+                }),
+            );
+        }
+        for balance in self.simulate_balance_overrides.iter() {
+            // Keep the rest of the account as it is on chain, so only the balance differs
+            let account = self
+                .guarded_rpc(
+                    client.get_account_with_commitment(&balance.pubkey, client.commitment()),
+                )
+                .await?
+                .value;
+            let (data, owner, executable, rent_epoch) = match account {
+                Some(account) => (
+                    account.data,
+                    account.owner,
+                    account.executable,
+                    account.rent_epoch,
+                ),
+                None => (vec![], system_program::id(), false, u64::MAX),
+            };
+            overrides.insert(
+                balance.pubkey.to_string(),
+                json!({
+                    "lamports": balance.lamports,
+                    "data": [BASE64.encode(data), "base64"],
+                    "owner": owner.to_string(),
+                    "executable": executable,
+                    "rentEpoch": rent_epoch,
+                }),
+            );
+        }
+        self.simulate_with_overrides(tx, config, overrides).await
+    }
+
+    async fn simulate_with_overrides(
+        &self,
+        tx: &impl MinerTransaction,
+        config: RpcSimulateTransactionConfig,
+        overrides: Map<String, Value>,
+    ) -> ClientResult<Response<RpcSimulateTransactionResult>> {
+        let mut config = serde_json::to_value(config).map_err(|err| ClientError {
+            request: None,
+            kind: ClientErrorKind::SerdeJson(err),
+        })?;
+        config["accountOverrides"] = Value::Object(overrides);
+        let serialized_tx = bincode::serialize(tx).map_err(|err| ClientError {
+            request: None,
+            kind: ClientErrorKind::Custom(format!("Failed to serialize transaction: {}", err)),
+        })?;
+        self.guarded_rpc(self.read_client().send(
+            RpcRequest::SimulateTransaction,
+            json!([BASE64.encode(serialized_tx), config]),
+        ))
+        .await
+    }
+
+    /// Whether the RPC node applies `accountOverrides`, by simulating a transfer paid for
+    /// by an unused address that only an override gives a balance. Nodes that ignore the
+    /// field fail it with `AccountNotFound`.
+    pub async fn supports_account_overrides(&self) -> ClientResult<bool> {
+        let probe = Keypair::new().pubkey();
+        let tx = Transaction::new_with_payer(
+            &[system_instruction::transfer(&probe, &probe, 1)],
+            Some(&probe),
+        );
+        let config = RpcSimulateTransactionConfig {
+            sig_verify: false,
+            replace_recent_blockhash: true,
+            encoding: Some(UiTransactionEncoding::Base64),
+            ..RpcSimulateTransactionConfig::default()
+        };
+        let mut overrides = Map::new();
+        overrides.insert(
+            probe.to_string(),
+            json!({
+                "lamports": PROGRAM_OVERRIDE_LAMPORTS,
+                "data": ["", "base64"],
+                "owner": system_program::id().to_string(),
+                "executable": false,
+                "rentEpoch": u64::MAX,
+            }),
+        );
+        let res = self.simulate_with_overrides(&tx, config, overrides).await?;
+        Ok(res.value.err.is_none())
+    }
+}
+
+/// Whether simulating a transaction over consecutive slots gave the same outcome.
+#[derive(Clone, Debug)]
+pub struct SimulationConsistencyReport {
+    pub consistent: bool,
+    pub varying_fields: Vec<String>,
+}
+
+impl Miner {
+    /// Simulates `tx` once per slot from the current slot through `slots` slots later and
+    /// compares the outcomes, for auditing programs that depend on slot timing.
+    pub async fn check_simulation_consistency(
+        &self,
+        tx: &impl MinerTransaction,
+        slots: u64,
+    ) -> ClientResult<SimulationConsistencyReport> {
+        let start = self.guarded_rpc(self.rpc_client.get_slot()).await?;
+        let mut outcomes = Vec::new();
+        for slot in start..=start.saturating_add(slots) {
+            while self.guarded_rpc(self.rpc_client.get_slot()).await? < slot {
+                tokio::time::sleep(Duration::from_millis(SLOT_POLL_DELAY)).await;
+            }
+            let res = self.request_simulation(tx).await?;
+            outcomes.push((res.value.err.is_none(), res.value.units_consumed));
+        }
+
+        let mut varying_fields = Vec::new();
+        if outcomes.iter().any(|(ok, _)| *ok != outcomes[0].0) {
+            varying_fields.push("result".to_string());
+        }
+        if outcomes.iter().any(|(_, cus)| *cus != outcomes[0].1) {
+            varying_fields.push("units_consumed".to_string());
+        }
+        Ok(SimulationConsistencyReport {
+            consistent: varying_fields.is_empty(),
+            varying_fields,
+        })
+    }
+}
@@ -0,0 +1,125 @@
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use futures::StreamExt;
+use solana_client::{nonblocking::pubsub_client::PubsubClient, pubsub_client::PubsubClientError};
+use solana_sdk::clock::Slot;
+
+use crate::{throttle::TransactionThrottle, Miner};
+
+// Assumed until enough slots have been seen to measure it
+const DEFAULT_SLOT_DURATION: Duration = Duration::from_millis(400);
+
+const RESUBSCRIBE_DELAY: Duration = Duration::from_millis(1000);
+
+// Weight of the newest sample in the moving average of slot durations
+const SLOT_DURATION_SMOOTHING: f64 = 0.2;
+
+struct SlotTiming {
+    slot: Slot,
+    started_at: Option<Instant>,
+    slot_duration: Duration,
+}
+
+/// Times submissions to land at the start of a slot rather than partway through one.
+pub struct SlotPacemaker {
+    offset: Duration,
+    timing: Mutex<SlotTiming>,
+}
+
+impl SlotPacemaker {
+    pub fn new(offset: Duration) -> Self {
+        Self {
+            offset,
+            timing: Mutex::new(SlotTiming {
+                slot: 0,
+                started_at: None,
+                slot_duration: DEFAULT_SLOT_DURATION,
+            }),
+        }
+    }
+
+    /// Records that `slot` started now.
+    pub fn record(&self, slot: Slot) {
+        let now = Instant::now();
+        let mut timing = self.timing.lock().expect("failed to lock mutex");
+        if slot <= timing.slot {
+            return;
+        }
+        if let Some(started_at) = timing.started_at {
+            let elapsed = now.duration_since(started_at) / (slot - timing.slot) as u32;
+            timing.slot_duration = timing.slot_duration.mul_f64(1.0 - SLOT_DURATION_SMOOTHING)
+                + elapsed.mul_f64(SLOT_DURATION_SMOOTHING);
+        }
+        timing.slot = slot;
+        timing.started_at = Some(now);
+    }
+
+    /// Sleeps until `offset` before the predicted start of the next slot. Returns
+    /// immediately if that is already the case or no slot has been seen yet.
+    pub async fn wait_for_slot_boundary(&self) {
+        let delay = {
+            let timing = self.timing.lock().expect("failed to lock mutex");
+            let Some(started_at) = timing.started_at else {
+                return;
+            };
+            let slot_nanos = timing.slot_duration.as_nanos().max(1);
+            let into_slot = started_at.elapsed().as_nanos() % slot_nanos;
+            let until_next = Duration::from_nanos((slot_nanos - into_slot) as u64);
+            until_next.saturating_sub(self.offset)
+        };
+        if !delay.is_zero() {
+            tokio::time::sleep(delay).await;
+        }
+    }
+}
+
+impl Miner {
+    /// Follows slot notifications in the background so `--tpu-submit-offset-ms` can
+    /// predict slot boundaries and `--max-txs-per-slot` can tell when a slot starts.
+    pub fn spawn_slot_pacemaker(&self) {
+        if self.slot_pacemaker.is_none() && self.throttle.is_none() {
+            return;
+        }
+        let pacemaker = self.slot_pacemaker.clone();
+        let throttle = self.throttle.clone();
+        let ws_url = self.rpc_ws_url.clone();
+        tokio::spawn(async move {
+            loop {
+                if let Err(err) =
+                    follow_slots(&ws_url, pacemaker.as_deref(), throttle.as_deref()).await
+                {
+                    println!("Error subscribing to slots: {}", err);
+                }
+                tokio::time::sleep(RESUBSCRIBE_DELAY).await;
+            }
+        });
+    }
+
+    pub async fn pace_submission(&self) {
+        if let Some(pacemaker) = self.slot_pacemaker.as_ref() {
+            pacemaker.wait_for_slot_boundary().await;
+        }
+    }
+}
+
+async fn follow_slots(
+    ws_url: &str,
+    pacemaker: Option<&SlotPacemaker>,
+    throttle: Option<&TransactionThrottle>,
+) -> Result<(), PubsubClientError> {
+    let client = PubsubClient::new(ws_url).await?;
+    let (mut slots, unsubscribe) = client.slot_subscribe().await?;
+    while let Some(info) = slots.next().await {
+        if let Some(pacemaker) = pacemaker {
+            pacemaker.record(info.slot);
+        }
+        if let Some(throttle) = throttle {
+            throttle.record(info.slot);
+        }
+    }
+    unsubscribe().await;
+    Ok(())
+}
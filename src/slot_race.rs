@@ -0,0 +1,85 @@
+use std::{fs, io, path::PathBuf, time::Duration};
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use solana_sdk::clock::Slot;
+
+use crate::Miner;
+
+// Another instance that checked in this recently is assumed to still be running
+const RACE_SLOT_WINDOW: Slot = 5;
+
+// About two slots, so check-ins stay well inside the race window
+const CHECK_IN_DELAY: u64 = 800;
+
+#[derive(Debug, Deserialize, Serialize)]
+struct InstanceLock {
+    instance_id: String,
+    slot: Slot,
+}
+
+/// Detects other miner instances sharing a lock file, which usually means the same
+/// keypair was deployed twice.
+pub struct SlotRaceDetector {
+    path: PathBuf,
+    instance_id: String,
+}
+
+impl SlotRaceDetector {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            instance_id: format!("{:016x}", rand::thread_rng().gen::<u64>()),
+        }
+    }
+
+    /// Records this instance as alive at `slot`, returning the id of any other instance
+    /// that checked in within the last few slots.
+    pub fn check_in(&self, slot: Slot) -> io::Result<Option<String>> {
+        let other = fs::read(&self.path)
+            .ok()
+            .and_then(|data| serde_json::from_slice::<InstanceLock>(&data).ok())
+            .filter(|lock| {
+                lock.instance_id != self.instance_id
+                    && slot.saturating_sub(lock.slot) <= RACE_SLOT_WINDOW
+            })
+            .map(|lock| lock.instance_id);
+        let lock = InstanceLock {
+            instance_id: self.instance_id.clone(),
+            slot,
+        };
+        fs::write(&self.path, serde_json::to_vec(&lock)?)?;
+        Ok(other)
+    }
+}
+
+impl Miner {
+    /// Checks in to the `--instance-lock-path` file every couple of slots in the background,
+    /// warning when another instance has checked in recently.
+    pub fn spawn_slot_race_detector(&self) {
+        let Some(detector) = self.slot_race_detector.clone() else {
+            return;
+        };
+        let client = self.rpc_client.clone();
+        tokio::spawn(async move {
+            let mut warned = None;
+            loop {
+                match client.get_slot().await {
+                    Ok(slot) => match detector.check_in(slot) {
+                        Ok(Some(instance_id)) if warned.as_ref() != Some(&instance_id) => {
+                            println!(
+                                "Warning: miner instance {} is also using {:?}, transactions may conflict",
+                                instance_id, detector.path
+                            );
+                            warned = Some(instance_id);
+                        }
+                        Ok(_) => {}
+                        Err(err) => println!("Failed to write {:?}: {}", detector.path, err),
+                    },
+                    Err(err) => println!("Failed to fetch slot for race detection: {}", err),
+                }
+                tokio::time::sleep(Duration::from_millis(CHECK_IN_DELAY)).await;
+            }
+        });
+    }
+}
@@ -0,0 +1,54 @@
+use std::collections::VecDeque;
+
+use solana_sdk::clock::Slot;
+
+/// A bounded, slot-ordered window of samples.
+pub struct SlotWindow<T> {
+    capacity: usize,
+    entries: VecDeque<(Slot, T)>,
+}
+
+impl<T> SlotWindow<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Adds a sample, evicting the oldest one if the window is full.
+    pub fn insert(&mut self, slot: Slot, value: T) {
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        // Keep entries sorted by slot, even if samples arrive slightly out of order
+        let index = self.entries.partition_point(|(s, _)| *s <= slot);
+        self.entries.insert(index, (slot, value));
+    }
+
+    pub fn values_since(&self, slot: Slot) -> impl Iterator<Item = &T> {
+        self.entries
+            .iter()
+            .filter(move |(s, _)| *s >= slot)
+            .map(|(_, value)| value)
+    }
+
+    pub fn evict_before(&mut self, slot: Slot) {
+        while self.entries.front().is_some_and(|(s, _)| *s < slot) {
+            self.entries.pop_front();
+        }
+    }
+}
+
+impl<T: Copy + Into<f64>> SlotWindow<T> {
+    /// Returns the `p`th percentile (0-100) of the samples using nearest-rank.
+    pub fn percentile(&self, p: f64) -> Option<f64> {
+        if self.entries.is_empty() {
+            return None;
+        }
+        let mut values: Vec<f64> = self.entries.iter().map(|(_, v)| (*v).into()).collect();
+        values.sort_by(|a, b| a.total_cmp(b));
+        let rank = ((p.clamp(0.0, 100.0) / 100.0) * values.len() as f64).ceil() as usize;
+        Some(values[rank.saturating_sub(1)])
+    }
+}
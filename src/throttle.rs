@@ -0,0 +1,76 @@
+use std::{sync::Mutex, time::Duration};
+
+use solana_sdk::clock::Slot;
+use tokio::sync::Notify;
+
+// A few slots without a notification means the slot feed has stalled
+const SLOT_FEED_STALL: Duration = Duration::from_millis(1200);
+
+/// Limits how many transactions are submitted within a single slot. Slots are learned from
+/// the pacemaker's slot notifications, passed in with `record`.
+pub struct TransactionThrottle {
+    max_per_slot: usize,
+    state: Mutex<(Slot, usize)>,
+    new_slot: Notify,
+}
+
+impl TransactionThrottle {
+    pub fn new(max_per_slot: usize) -> Self {
+        Self {
+            max_per_slot: max_per_slot.max(1),
+            state: Mutex::new((0, 0)),
+            new_slot: Notify::new(),
+        }
+    }
+
+    /// Records that `slot` started, opening its permits.
+    pub fn record(&self, slot: Slot) {
+        let mut state = self.state.lock().expect("failed to lock mutex");
+        if slot > state.0 {
+            *state = (slot, 0);
+            self.new_slot.notify_waiters();
+        }
+    }
+
+    /// Waits until a permit is available in the current slot. If the slot feed stalls,
+    /// a fresh set of permits is opened rather than holding sends back indefinitely.
+    pub async fn acquire(&self) {
+        loop {
+            let new_slot = self.new_slot.notified();
+            {
+                let mut state = self.state.lock().expect("failed to lock mutex");
+                if state.1 < self.max_per_slot {
+                    state.1 += 1;
+                    return;
+                }
+            }
+            if tokio::time::timeout(SLOT_FEED_STALL, new_slot)
+                .await
+                .is_err()
+            {
+                self.state.lock().expect("failed to lock mutex").1 = 0;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::TransactionThrottle;
+
+    #[tokio::test(start_paused = true)]
+    async fn waits_for_the_next_slot_once_permits_run_out() {
+        let throttle = TransactionThrottle::new(1);
+        throttle.record(10);
+        throttle.acquire().await;
+        assert!(
+            tokio::time::timeout(Duration::from_millis(100), throttle.acquire())
+                .await
+                .is_err()
+        );
+        throttle.record(11);
+        throttle.acquire().await;
+    }
+}
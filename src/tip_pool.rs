@@ -0,0 +1,93 @@
+use solana_program::{pubkey::Pubkey, system_instruction, system_program};
+use solana_sdk::{nonce::State, signature::Signer};
+
+use crate::Miner;
+
+/// Tip accounts are derived from the signer, so the pool needs no separate record.
+fn tip_account_seed(index: usize) -> String {
+    format!("ore-tip-{}", index)
+}
+
+pub fn tip_account_address(authority: &Pubkey, index: usize) -> Pubkey {
+    Pubkey::create_with_seed(authority, &tip_account_seed(index), &system_program::ID).unwrap()
+}
+
+/// The durable nonce accounts of the tip pool. Bundles use them round-robin by index, so
+/// consecutive bundles never share a nonce that has not been advanced yet. Each run of the
+/// CLI starts over, so the index is passed in rather than kept here.
+pub struct TipAccountRotator {
+    accounts: Vec<Pubkey>,
+}
+
+impl TipAccountRotator {
+    pub fn new(authority: &Pubkey, size: usize) -> Self {
+        Self {
+            accounts: (0..size)
+                .map(|index| tip_account_address(authority, index))
+                .collect(),
+        }
+    }
+
+    pub fn get(&self, index: usize) -> Option<Pubkey> {
+        self.accounts.get(index).copied()
+    }
+}
+
+impl Miner {
+    /// Creates the first `size` tip accounts of the pool that do not exist yet, each
+    /// funded with the rent-exempt minimum for a nonce account.
+    pub async fn setup_tip_pool(&self, size: usize) {
+        let signer = self.signer();
+        let lamports = match self
+            .rpc_client
+            .get_minimum_balance_for_rent_exemption(State::size())
+            .await
+        {
+            Ok(lamports) => lamports,
+            Err(err) => {
+                println!("Error: {}", err);
+                return;
+            }
+        };
+        for index in 0..size {
+            let address = tip_account_address(&signer.pubkey(), index);
+            if self.rpc_client.get_account(&address).await.is_ok() {
+                println!("Tip account {} already exists: {}", index, address);
+                continue;
+            }
+            let ixs = system_instruction::create_nonce_account_with_seed(
+                &signer.pubkey(),
+                &address,
+                &signer.pubkey(),
+                &tip_account_seed(index),
+                &signer.pubkey(),
+                lamports,
+            );
+            match self.send_and_confirm(&ixs, false, false).await {
+                Ok(_) => println!("Created tip account {}: {}", index, address),
+                Err(err) => {
+                    println!("Failed to create tip account {}: {}", index, err);
+                    return;
+                }
+            }
+        }
+    }
+
+    /// The tip account a bundle used, by its pool index, when `--tip-pool-size` is set.
+    pub fn tip_account(&self, index: usize) -> Option<Pubkey> {
+        let account = self.tip_rotator.as_ref()?.get(index);
+        if account.is_none() {
+            println!("Tip account {} is outside the tip pool", index);
+        }
+        account
+    }
+
+    /// Advances the nonce of the tip account a landed bundle used.
+    pub async fn advance_tip_account(&self, account: Pubkey) {
+        let ix = system_instruction::advance_nonce_account(&account, &self.signer().pubkey());
+        match self.send_and_confirm(&[ix], false, false).await {
+            Ok(_) => println!("Advanced the nonce of tip account {}", account),
+            Err(err) => println!("Failed to advance the nonce of {}: {}", account, err),
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use std::{net::SocketAddr, sync::Arc};
+
+use solana_client::{
+    client_error::{ClientError, ClientErrorKind, Result as ClientResult},
+    connection_cache::ConnectionCache,
+    nonblocking::rpc_client::RpcClient,
+};
+use solana_connection_cache::nonblocking::client_connection::ClientConnection;
+use solana_sdk::signature::Signature;
+
+use crate::{gossip::GossipListener, transaction::MinerTransaction};
+
+const CONNECTION_POOL_SIZE: usize = 1;
+
+// Slots ahead whose leaders each transaction is sent to
+const LEADER_FANOUT_SLOTS: u64 = 12;
+
+/// Submits transactions to validator TPU ports over QUIC instead of through RPC.
+pub enum TpuSender {
+    /// Fans out to the upcoming leaders, at the QUIC TPU addresses they advertise through
+    /// gossip
+    Leaders {
+        connection_cache: ConnectionCache,
+        rpc_client: Arc<RpcClient>,
+        gossip: Arc<GossipListener>,
+    },
+    /// Sends every transaction to one validator given with `--validator-tpu-host`
+    Validator {
+        connection_cache: ConnectionCache,
+        addr: SocketAddr,
+    },
+}
+
+impl TpuSender {
+    pub async fn new(
+        rpc_client: Arc<RpcClient>,
+        validator: Option<SocketAddr>,
+    ) -> Result<Self, String> {
+        let connection_cache = ConnectionCache::new_quic("ore-cli", CONNECTION_POOL_SIZE);
+        if let Some(addr) = validator {
+            return Ok(TpuSender::Validator {
+                connection_cache,
+                addr,
+            });
+        }
+        let gossip = Arc::new(GossipListener::default());
+        gossip
+            .refresh(&rpc_client)
+            .await
+            .map_err(|err| err.to_string())?;
+        gossip.spawn_refresh(rpc_client.clone());
+        Ok(TpuSender::Leaders {
+            connection_cache,
+            rpc_client,
+            gossip,
+        })
+    }
+
+    pub async fn send(&self, tx: &impl MinerTransaction) -> ClientResult<Signature> {
+        let wire_transaction = bincode::serialize(tx).map_err(|err| ClientError {
+            request: None,
+            kind: ClientErrorKind::Custom(format!("Failed to serialize transaction: {}", err)),
+        })?;
+        match self {
+            TpuSender::Leaders {
+                connection_cache,
+                rpc_client,
+                gossip,
+            } => {
+                let tpus = upcoming_leader_tpus(rpc_client, gossip).await?;
+                if tpus.is_empty() {
+                    return Err(ClientError {
+                        request: None,
+                        kind: ClientErrorKind::Custom(
+                            "No upcoming leader advertises a QUIC TPU address".into(),
+                        ),
+                    });
+                }
+                // Sent if any leader accepts it
+                let mut last_err = None;
+                let mut sent = false;
+                for tpu in tpus.iter() {
+                    match connection_cache
+                        .get_nonblocking_connection(tpu)
+                        .send_data(&wire_transaction)
+                        .await
+                    {
+                        Ok(()) => sent = true,
+                        Err(err) => last_err = Some(err),
+                    }
+                }
+                if let (false, Some(err)) = (sent, last_err) {
+                    return Err(err.into());
+                }
+            }
+            TpuSender::Validator {
+                connection_cache,
+                addr,
+            } => {
+                connection_cache
+                    .get_nonblocking_connection(addr)
+                    .send_data(&wire_transaction)
+                    .await?
+            }
+        }
+        Ok(*tx.get_signature())
+    }
+}
+
+/// The QUIC TPU addresses of the leaders of the next `LEADER_FANOUT_SLOTS` slots.
+async fn upcoming_leader_tpus(
+    rpc_client: &RpcClient,
+    gossip: &GossipListener,
+) -> ClientResult<Vec<SocketAddr>> {
+    let slot = rpc_client.get_slot().await?;
+    let leaders = rpc_client
+        .get_slot_leaders(slot, LEADER_FANOUT_SLOTS)
+        .await?;
+    let mut tpus = vec![];
+    for tpu in leaders
+        .into_iter()
+        .filter_map(|leader| gossip.tpu_for_leader(leader))
+    {
+        if !tpus.contains(&tpu) {
+            tpus.push(tpu);
+        }
+    }
+    Ok(tpus)
+}
@@ -0,0 +1,106 @@
+use ed25519_dalek::{PublicKey, Signature as DalekSignature};
+use solana_client::rpc_client::SerializableTransaction;
+use solana_program::{instruction::CompiledInstruction, pubkey::Pubkey};
+use solana_sdk::{
+    signature::Signature,
+    transaction::{Transaction, VersionedTransaction},
+};
+
+use crate::error::MinerError;
+
+/// What the simulate, send, and confirm pipeline needs to know about a transaction,
+/// whether it uses a legacy or a versioned message.
+pub trait MinerTransaction: SerializableTransaction {
+    /// Accounts listed directly in the message, including the fee payer and program ids.
+    fn static_account_keys(&self) -> &[Pubkey];
+
+    fn instructions(&self) -> &[CompiledInstruction];
+
+    fn num_required_signatures(&self) -> u8;
+
+    fn signatures(&self) -> &[Signature];
+
+    /// The serialized message the signatures sign.
+    fn message_data(&self) -> Vec<u8>;
+
+    /// Unique accounts the transaction loads, including those from lookup tables.
+    fn account_count(&self) -> usize {
+        self.static_account_keys().len()
+    }
+}
+
+impl MinerTransaction for Transaction {
+    fn static_account_keys(&self) -> &[Pubkey] {
+        &self.message.account_keys
+    }
+
+    fn instructions(&self) -> &[CompiledInstruction] {
+        &self.message.instructions
+    }
+
+    fn num_required_signatures(&self) -> u8 {
+        self.message.header.num_required_signatures
+    }
+
+    fn signatures(&self) -> &[Signature] {
+        &self.signatures
+    }
+
+    fn message_data(&self) -> Vec<u8> {
+        self.message.serialize()
+    }
+}
+
+impl MinerTransaction for VersionedTransaction {
+    fn static_account_keys(&self) -> &[Pubkey] {
+        self.message.static_account_keys()
+    }
+
+    fn instructions(&self) -> &[CompiledInstruction] {
+        self.message.instructions()
+    }
+
+    fn num_required_signatures(&self) -> u8 {
+        self.message.header().num_required_signatures
+    }
+
+    fn signatures(&self) -> &[Signature] {
+        &self.signatures
+    }
+
+    fn message_data(&self) -> Vec<u8> {
+        self.message.serialize()
+    }
+
+    fn account_count(&self) -> usize {
+        let loaded = self.message.address_table_lookups().map_or(0, |lookups| {
+            lookups
+                .iter()
+                .map(|lookup| lookup.writable_indexes.len() + lookup.readonly_indexes.len())
+                .sum()
+        });
+        self.static_account_keys().len() + loaded
+    }
+}
+
+/// Checks every signature of a signed transaction against its message locally, so a
+/// mismatched keypair fails before the transaction is sent.
+pub fn verify_transaction_signature(tx: &impl MinerTransaction) -> Result<(), MinerError> {
+    let message = tx.message_data();
+    let signers = tx
+        .static_account_keys()
+        .iter()
+        .take(tx.num_required_signatures() as usize);
+    for (pubkey, signature) in signers.zip(tx.signatures()) {
+        let verified = PublicKey::from_bytes(pubkey.as_ref())
+            .and_then(|key| {
+                let signature = DalekSignature::from_bytes(signature.as_ref())?;
+                key.verify_strict(&message, &signature)
+            })
+            .is_ok();
+        if !verified {
+            return Err(MinerError::SignatureVerificationFailed(*pubkey));
+        }
+    }
+    Ok(())
+}
@@ -1,4 +1,7 @@
+use std::fmt::Write;
+
 use cached::proc_macro::cached;
+use clap::ValueEnum;
 use ore::{
     self,
     state::{Proof, Treasury},
@@ -6,8 +9,8 @@ use ore::{
     MINT_ADDRESS, PROOF, TREASURY_ADDRESS,
 };
 use solana_client::nonblocking::rpc_client::RpcClient;
-use solana_program::{pubkey::Pubkey, sysvar};
-use solana_sdk::clock::Clock;
+use solana_program::{instruction::Instruction, pubkey::Pubkey, sysvar};
+use solana_sdk::{clock::Clock, signature::Signature};
 use spl_associated_token_account::get_associated_token_address;
 
 pub async fn get_treasury(client: &RpcClient) -> Treasury {
@@ -44,3 +47,65 @@ pub fn proof_pubkey(authority: Pubkey) -> Pubkey {
 pub fn treasury_tokens_pubkey() -> Pubkey {
     get_associated_token_address(&TREASURY_ADDRESS, &MINT_ADDRESS)
 }
+
+/// Formats an instruction as `<program_id_short>:<discriminator_hex>(<num_accounts>a,<data_len>b)`.
+pub fn encode_instruction_compact(ix: &Instruction) -> String {
+    let program_id = ix.program_id.to_string();
+    let discriminator = ix
+        .data
+        .first()
+        .map(|byte| format!("{:02x}", byte))
+        .unwrap_or_default();
+    format!(
+        "{}:{}({}a,{}b)",
+        &program_id[..program_id.len().min(8)],
+        discriminator,
+        ix.accounts.len(),
+        ix.data.len()
+    )
+}
+
+pub fn encode_instructions_compact(ixs: &[Instruction]) -> String {
+    ixs.iter()
+        .map(encode_instruction_compact)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut out, b| {
+        write!(out, "{:02x}", b).ok();
+        out
+    })
+}
+
+pub fn from_hex(hex: &str) -> Result<Vec<u8>, String> {
+    if hex.len() % 2 != 0 {
+        return Err(format!("odd number of hex digits in `{}`", hex));
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            hex.get(i..i + 2)
+                .filter(|byte| byte.bytes().all(|b| b.is_ascii_hexdigit()))
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                .ok_or_else(|| format!("invalid hex `{}`", hex))
+        })
+        .collect()
+}
+
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+pub enum SignatureDisplay {
+    #[default]
+    Base58,
+    Hex,
+}
+
+impl SignatureDisplay {
+    pub fn format(&self, sig: &Signature) -> String {
+        match self {
+            SignatureDisplay::Base58 => sig.to_string(),
+            SignatureDisplay::Hex => to_hex(sig.as_ref()),
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use clap::ValueEnum;
+use rand::Rng;
+use solana_client::client_error::{ClientError, ClientErrorKind, Result as ClientResult};
+use solana_program::{hash::Hash, instruction::Instruction, pubkey::Pubkey};
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+
+use crate::{
+    annotation::{TransactionAnnotation, LAMPORTS_PER_SIGNATURE},
+    compute_budget::{static_compute_unit_limit, transaction_compute_unit_price},
+    Miner,
+};
+
+/// A signer that can pay for transactions sent from any task.
+pub trait TransactionSigner: Signer + Send + Sync {
+    fn as_signer(self: Arc<Self>) -> Arc<dyn Signer>;
+}
+
+impl<T: Signer + Send + Sync + 'static> TransactionSigner for T {
+    fn as_signer(self: Arc<Self>) -> Arc<dyn Signer> {
+        self
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+pub enum SelectionStrategy {
+    /// Each keypair in turn
+    #[default]
+    RoundRobin,
+    /// A keypair chosen at random for each transaction
+    Random,
+    /// The keypair with the lowest balance that can still pay, to spend them down evenly
+    LowestBalance,
+}
+
+/// Spreads the fees of mining transactions across several keypairs, so no single pubkey
+/// pays for every submission.
+pub struct WalletMultiplexer {
+    keypairs: Vec<Arc<dyn TransactionSigner>>,
+    strategy: SelectionStrategy,
+    next: AtomicUsize,
+}
+
+impl WalletMultiplexer {
+    pub fn new(keypairs: Vec<Arc<dyn TransactionSigner>>, strategy: SelectionStrategy) -> Self {
+        Self {
+            keypairs,
+            strategy,
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn pubkeys(&self) -> impl Iterator<Item = Pubkey> + '_ {
+        self.keypairs.iter().map(|keypair| keypair.pubkey())
+    }
+
+    /// Picks the keypair to pay for the next transaction, given the balance of each and
+    /// the fee the transaction is expected to cost.
+    pub fn select(&self, fee: u64, balance: impl Fn(&Pubkey) -> u64) -> Arc<dyn TransactionSigner> {
+        let index = match self.strategy {
+            SelectionStrategy::RoundRobin => {
+                self.next.fetch_add(1, Ordering::Relaxed) % self.keypairs.len()
+            }
+            SelectionStrategy::Random => rand::thread_rng().gen_range(0..self.keypairs.len()),
+            SelectionStrategy::LowestBalance => self
+                .keypairs
+                .iter()
+                .enumerate()
+                .map(|(index, keypair)| (index, balance(&keypair.pubkey())))
+                .filter(|(_, balance)| *balance >= fee)
+                .min_by_key(|(_, balance)| *balance)
+                .map_or(0, |(index, _)| index),
+        };
+        self.keypairs[index].clone()
+    }
+}
+
+/// The keypairs to sign a transaction with `required` signers: `payer`, and `signer` too
+/// if the transaction's instructions need its signature.
+pub fn payer_signers(
+    payer: Option<Arc<dyn TransactionSigner>>,
+    signer: &Keypair,
+    required: &[Pubkey],
+) -> Vec<Arc<dyn Signer>> {
+    let Some(payer) = payer else {
+        return vec![Arc::new(signer.insecure_clone())];
+    };
+    let needs_signer = payer.pubkey() != signer.pubkey() && required.contains(&signer.pubkey());
+    let mut signers = vec![payer.as_signer()];
+    if needs_signer {
+        signers.push(Arc::new(signer.insecure_clone()));
+    }
+    signers
+}
+
+/// Signs `tx` with `payer`, and with `signer` too if the transaction's instructions
+/// need its signature.
+pub fn sign_with_payer(
+    tx: &mut Transaction,
+    payer: Arc<dyn TransactionSigner>,
+    signer: &Keypair,
+    blockhash: Hash,
+) -> ClientResult<()> {
+    let required = &tx.message.account_keys[..tx.message.header.num_required_signatures as usize];
+    let signers = payer_signers(Some(payer), signer, required);
+    tx.try_sign(&signers, blockhash).map_err(|err| ClientError {
+        request: None,
+        kind: ClientErrorKind::Custom(format!("Failed to sign transaction: {}", err)),
+    })
+}
+
+/// Signs `tx` like `sign_with_payer`, falling back to `signer` alone without a `payer`,
+/// and also with each of `cosigners` the transaction's instructions need.
+pub fn sign_with_cosigners(
+    tx: &mut Transaction,
+    payer: Option<Arc<dyn TransactionSigner>>,
+    signer: &Keypair,
+    cosigners: &[Arc<Keypair>],
+    blockhash: Hash,
+) -> ClientResult<()> {
+    let required = &tx.message.account_keys[..tx.message.header.num_required_signatures as usize];
+    let mut signers = payer_signers(payer, signer, required);
+    for cosigner in cosigners {
+        if required.contains(&cosigner.pubkey())
+            && !signers.iter().any(|s| s.pubkey() == cosigner.pubkey())
+        {
+            signers.push(cosigner.clone());
+        }
+    }
+    tx.try_sign(&signers, blockhash).map_err(|err| ClientError {
+        request: None,
+        kind: ClientErrorKind::Custom(format!("Failed to sign transaction: {}", err)),
+    })
+}
+
+impl Miner {
+    /// The keypair to pay for the next transaction, built from `ixs`, when
+    /// `--multiplex-keypairs` or keypair rotation is set.
+    pub fn fee_payer(&self, ixs: &[Instruction]) -> Option<Arc<dyn TransactionSigner>> {
+        if let Some(rotator) = self.key_rotator.as_ref() {
+            return Some(rotator.current());
+        }
+        let multiplexer = self.wallet_multiplexer.as_ref()?;
+        Some(multiplexer.select(self.estimated_fee(ixs), |pubkey| {
+            self.account_cache
+                .get(pubkey)
+                .map_or(0, |account| account.lamports)
+        }))
+    }
+
+    /// The fee a separate payer would pay for a transaction built from `ixs`, budgeted at
+    /// the static compute unit limit if `ixs` do not set a price themselves.
+    fn estimated_fee(&self, ixs: &[Instruction]) -> u64 {
+        let tx = Transaction::new_with_payer(ixs, None);
+        let tx = if transaction_compute_unit_price(&tx) == 0 {
+            let units = static_compute_unit_limit(ixs) as u64;
+            Transaction::new_with_payer(&self.budget_instructions(ixs, units), None)
+        } else {
+            tx
+        };
+        TransactionAnnotation::new(&tx, None)
+            .fee_lamports
+            .saturating_add(LAMPORTS_PER_SIGNATURE)
+    }
+}